@@ -1,4 +1,17 @@
 pub mod model;
 pub mod sui;
+pub mod sui_builder;
+pub mod sui_checkpoint;
+pub mod sui_cursor;
+pub mod sui_endpoint;
+pub mod sui_error;
+pub mod sui_follower;
 pub mod sui_func;
+pub mod sui_keystore;
 pub mod sui_lib;
+pub mod sui_multisig;
+pub mod sui_offline;
+pub mod sui_ptb;
+pub mod sui_retry;
+pub mod sui_signer;
+pub mod sui_watcher;
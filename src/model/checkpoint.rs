@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::{Checkpoint, SuiTransactionBlockResponse};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointSummary {
+    pub sequence_number: u64,
+    pub digest: String,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+    /// `None` only for the genesis checkpoint.
+    pub previous_digest: Option<String>,
+    pub transactions: Vec<String>,
+    /// Gas totals of the epoch up to and including this checkpoint.
+    pub rolling_computation_cost: u64,
+    pub rolling_storage_cost: u64,
+    pub rolling_storage_rebate: u64,
+    pub rolling_non_refundable_storage_fee: u64,
+    /// Transactions executed on the network up to and including this checkpoint.
+    pub network_total_transactions: u64,
+}
+
+impl From<Checkpoint> for CheckpointSummary {
+    fn from(checkpoint: Checkpoint) -> Self {
+        let gas_summary = checkpoint.epoch_rolling_gas_cost_summary;
+        CheckpointSummary {
+            sequence_number: checkpoint.sequence_number,
+            digest: checkpoint.digest.to_string(),
+            epoch: checkpoint.epoch,
+            timestamp_ms: checkpoint.timestamp_ms,
+            previous_digest: checkpoint.previous_digest.map(|digest| digest.to_string()),
+            transactions: checkpoint
+                .transactions
+                .iter()
+                .map(|digest| digest.base58_encode())
+                .collect(),
+            rolling_computation_cost: gas_summary.computation_cost,
+            rolling_storage_cost: gas_summary.storage_cost,
+            rolling_storage_rebate: gas_summary.storage_rebate,
+            rolling_non_refundable_storage_fee: gas_summary.non_refundable_storage_fee,
+            network_total_transactions: checkpoint.network_total_transactions,
+        }
+    }
+}
+
+/// A checkpoint together with the full responses of its transactions, in
+/// checkpoint order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointWithTransactions {
+    pub checkpoint: CheckpointSummary,
+    pub transactions: Vec<SuiTransactionBlockResponse>,
+}
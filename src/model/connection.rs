@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub rpc_url: String,
+    pub rpc_api_version: String,
+    pub chain_identifier: String,
+    pub latest_checkpoint: u64,
+    pub reference_gas_price: u64,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EndpointMetrics {
+    pub url: String,
+    pub connected: bool,
+    pub healthy: bool,
+    pub requests: u64,
+    pub failures: u64,
+    pub rate_limited: u64,
+    pub last_latency_ms: Option<u64>,
+    pub average_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl EndpointMetrics {
+    pub fn new(url: String) -> Self {
+        EndpointMetrics {
+            url,
+            connected: false,
+            healthy: false,
+            requests: 0,
+            failures: 0,
+            rate_limited: 0,
+            last_latency_ms: None,
+            average_latency_ms: None,
+            last_error: None,
+        }
+    }
+}
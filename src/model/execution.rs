@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::{BalanceChange, OwnedObjectRef};
+
+/// Result of an executed transaction, read from its effects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionOutcome {
+    pub digest: String,
+    pub success: bool,
+    /// `success`, or the execution error reported by the node.
+    pub status: String,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    /// Computation plus storage cost minus the storage rebate.
+    pub gas_used: i64,
+    pub created: Vec<OwnedObjectRef>,
+    pub mutated: Vec<OwnedObjectRef>,
+    pub balance_changes: Vec<BalanceChange>,
+    /// Only known once the transaction is part of a checkpoint, see `WaitMode::Checkpoint`.
+    pub checkpoint: Option<u64>,
+    pub timestamp_ms: Option<u64>,
+}
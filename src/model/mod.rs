@@ -1 +1,8 @@
 pub mod balance;
+pub mod checkpoint;
+pub mod connection;
+pub mod endpoint;
+pub mod execution;
+pub mod payment;
+pub mod preview;
+pub mod read;
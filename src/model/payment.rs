@@ -0,0 +1,32 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::execution::ExecutionOutcome;
+use crate::model::preview::DryRunPreview;
+use crate::sui_error::SuiChainResult;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Payment {
+    pub recipient: String,
+    /// In base units of `coin_type`.
+    pub amount: u64,
+    /// Full coin type, e.g. `0x2::sui::SUI`.
+    pub coin_type: String,
+}
+
+/// One transaction of a batch payment.
+#[derive(Debug)]
+pub struct BatchChunk {
+    /// Indexes of the payments covered by this transaction.
+    pub payments: Range<usize>,
+    /// The executed transaction, or why this chunk was not paid.
+    pub result: SuiChainResult<ExecutionOutcome>,
+}
+
+/// One simulated transaction of a batch payment.
+#[derive(Debug)]
+pub struct BatchChunkPreview {
+    pub payments: Range<usize>,
+    pub result: SuiChainResult<DryRunPreview>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::{BalanceChange, SuiTransactionBlockEffects};
+
+use crate::model::balance::AccountHistory;
+
+/// What a write operation would do, simulated without executing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunPreview {
+    pub success: bool,
+    /// `success`, or the execution error reported by the node.
+    pub status: String,
+    pub gas_budget: u64,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    /// Computation plus storage cost minus the storage rebate.
+    pub net_gas_cost: i64,
+    pub balance_changes: Vec<BalanceChange>,
+    pub effects: SuiTransactionBlockEffects,
+    /// The balance changes of the sender, rendered like the wallet history.
+    pub history: Vec<AccountHistory>,
+}
@@ -0,0 +1,31 @@
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+/// Result of a paged read. `errors` holds the failures that were skipped after
+/// retrying, so an empty list means the data is complete.
+#[derive(Debug)]
+pub struct ReadResult<T> {
+    pub data: T,
+    pub errors: Vec<SuiChainError>,
+}
+
+impl<T> ReadResult<T> {
+    pub fn complete(data: T) -> Self {
+        ReadResult {
+            data,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the data only if nothing was skipped, otherwise the first error.
+    pub fn into_complete(mut self) -> SuiChainResult<T> {
+        if self.errors.is_empty() {
+            Ok(self.data)
+        } else {
+            Err(self.errors.remove(0))
+        }
+    }
+}
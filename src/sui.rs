@@ -1,326 +1,1044 @@
-use std::{borrow::BorrowMut, str::FromStr};
+use std::{
+    future::Future,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use anyhow::anyhow;
-use base64::{prelude::BASE64_STANDARD, Engine};
-use fastcrypto::hash::{Blake2b256, HashFunction};
-use shared_crypto::intent::{Intent, IntentMessage};
 use sui_json_rpc_types::{
-    SuiGasData, SuiObjectData, SuiObjectDataOptions, SuiPastObjectResponse, SuiTransactionBlockData, SuiTransactionBlockResponse
+    CheckpointId, Coin, DevInspectResults, DryRunTransactionBlockResponse, SuiExecutionStatus,
+    SuiGasData, SuiObjectData, SuiObjectDataOptions, SuiPastObjectResponse, SuiTransactionBlock,
+    SuiTransactionBlockData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
 };
 use sui_sdk::{
     rpc_types::SuiTransactionBlockResponseOptions, types::transaction::TransactionData, SuiClient,
-    SuiClientBuilder,
 };
-use sui_types::base_types::SuiAddress;
-use sui_types::crypto::SuiSignature;
-use sui_types::crypto::{Signer, SuiKeyPair};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::{SignatureScheme, SuiKeyPair};
 use sui_types::digests::TransactionDigest;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::transaction::{
+    Argument, ProgrammableTransaction, Transaction, TransactionDataAPI, TransactionKind,
+};
 use sui_types::signature::GenericSignature;
-use sui_types::sui_serde::BigInt;
 
 use crate::model::balance::{
     AccountHistory, CoinDetail, ComplexTransactionDetails, ReceiveTokenDetails, SentTokenDetails,
     StakeDetail, SwapDetails,
 };
+use crate::model::checkpoint::CheckpointSummary;
+use crate::model::connection::ConnectionInfo;
+use crate::model::endpoint::EndpointMetrics;
+use crate::model::execution::ExecutionOutcome;
+use crate::model::payment::{BatchChunk, BatchChunkPreview, Payment};
+use crate::model::preview::DryRunPreview;
+use crate::model::read::ReadResult;
+
+use super::sui_builder::{NetworkConfig, SuiNetworkBuilder, WaitMode};
+use super::sui_endpoint::EndpointPool;
+use super::sui_error::{SuiChainError, SuiChainResult};
+use super::sui_func::{keypair_to_bech32, mnemonic_to_keypair, parse_private_key};
+use super::sui_keystore::SuiKeystore;
+use super::sui_lib::{
+    fetch_sorted_gas_coins, is_sui_coin_type, select_gas_coins, sui_format_volume,
+    sui_get_currency_name, sui_owner_address, SuiWalletFuncExtension, MAX_GAS_COINS,
+};
+use super::sui_offline::{signature_from_base64, transaction_from_base64};
+use super::sui_ptb::TransactionBlockBuilder;
+use super::sui_signer::{sign_transaction, KeyPairSigner, TransactionSigner};
+
+/// Upper bound for the budget used while estimating gas with a dry run.
+const MAX_DRY_RUN_GAS_BUDGET: u64 = 50_000_000_000;
+const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
+/// Payments per transaction in `batch_pay`, below the 512 arguments a single
+/// command accepts and the 1024 commands a transaction accepts.
+pub const BATCH_PAY_CHUNK_SIZE: usize = 500;
+/// Coins merged by one `MergeCoins` command, which also takes the target coin.
+const MAX_MERGE_SOURCES: usize = 500;
 
-use super::sui_lib::{sui_format_volume, sui_get_currency_name, SuiWalletFuncExtension};
+/// Cheap to clone: clones share the endpoint pool, so one connection can serve
+/// many concurrent tasks. Each clone carries its own signer.
+#[derive(Clone)]
 pub struct SuiNetwork {
-    node_addr: String,
-    sui_client: Option<SuiClient>,
-    key_pair: Option<SuiKeyPair>,
-    public_addr_obj: Option<SuiAddress>,
-    public_addr_str: String,
+    config: Arc<NetworkConfig>,
+    pool: Arc<EndpointPool>,
+    signer: Option<Arc<dyn TransactionSigner>>,
+}
+
+// Reads are issued from many tasks at once, keep the handle thread safe.
+#[allow(dead_code)]
+fn assert_shareable() {
+    fn is_send_sync<T: Send + Sync + Clone>() {}
+    is_send_sync::<SuiNetwork>();
 }
 
 impl SuiNetwork {
     pub fn new(node_addr: Option<String>) -> Self {
-        let node_addr = match node_addr {
-            Some(node_addr) => node_addr,
-            None => "https://fullnode.mainnet.sui.io:443".to_string(),
-        };
+        let mut config = SuiNetworkBuilder::default().config();
+        if let Some(node_addr) = node_addr {
+            config.network = None;
+            config.rpc_url = node_addr;
+        }
+        SuiNetwork::with_config(config)
+    }
+    pub fn builder() -> SuiNetworkBuilder {
+        SuiNetworkBuilder::default()
+    }
+    pub fn with_config(config: NetworkConfig) -> Self {
         SuiNetwork {
-            node_addr: node_addr,
-            sui_client: None,
-            key_pair: None,
-            public_addr_obj: None,
-            public_addr_str: "".to_string(),
+            pool: Arc::new(EndpointPool::new(config.rpc_urls(), config.endpoint_policy)),
+            config: Arc::new(config),
+            signer: None,
         }
     }
-    pub async fn connect(&mut self) -> bool {
-        match SuiClientBuilder::default().build(&self.node_addr).await {
-            Ok(sui_client) => {
-                self.sui_client = Some(sui_client);
-                true
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+    #[tracing::instrument(skip(self), fields(rpc_url = %self.config.rpc_url))]
+    pub async fn connect(&self) -> SuiChainResult<ConnectionInfo> {
+        let connection_info = self.pool.connect(&self.config).await?;
+        tracing::info!(
+            chain_identifier = %connection_info.chain_identifier,
+            rpc_api_version = %connection_info.rpc_api_version,
+            latest_checkpoint = connection_info.latest_checkpoint,
+            "connected to SUI node"
+        );
+        Ok(connection_info)
+    }
+    /// Runs a read call through the endpoint pool, retrying it with the configured policy.
+    pub(crate) async fn read<T, F, Fut>(&self, request: F) -> SuiChainResult<T>
+    where
+        F: Fn(SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, sui_sdk::error::Error>>,
+    {
+        self.config
+            .retry_policy
+            .run(|| self.pool.call(&request))
+            .await
+    }
+    pub fn endpoint_metrics(&self) -> Vec<EndpointMetrics> {
+        self.pool.metrics()
+    }
+    /// Periodically pings every endpoint and reconnects the ones that are down.
+    /// Must be called from within a tokio runtime.
+    pub fn spawn_health_probe(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let pool = self.pool.clone();
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                pool.probe(&config).await;
             }
-            Err(_) => false,
-        }
+        })
     }
 
+    #[tracing::instrument(skip(self), fields(sender = %self.get_my_address()))]
     pub async fn transfer(
-        &mut self,
+        &self,
         receiver: String,
         volume: u64,
-    ) -> Result<String, anyhow::Error> {
-        let receiver = SuiAddress::from_str(&receiver.clone());
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
-        }
-        if self.key_pair.is_none() {
-            return Err(anyhow::Error::msg("not-assigned-private-key"));
-        }
-        if receiver.is_err() {
-            return Err(anyhow::Error::msg("wrong-receiver-address"));
-        }
-        let receiver = receiver.unwrap();
-        println!("Sender: {:?}", self.public_addr_str.clone());
-        println!("receiver: {:?}", receiver.clone());
-        println!("---------------------------------------");
-
-        let key_pair = self.key_pair.as_mut().unwrap().borrow_mut().copy();
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
-        let gas_coin = sui_client
-            .coin_read_api()
-            .get_coins(self.public_addr_obj.unwrap(), None, None, None)
-            .await
-            .unwrap()
-            .data
-            .into_iter()
-            .next()
-            .ok_or(anyhow!("No coins found for sender"))
-            .unwrap();
-        let gas_budget = 5_000_000;
-        let gas_price = sui_client
-            .read_api()
-            .get_reference_gas_price()
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let sender = signer.address();
+
+        let (tx_data, dry_run) = self.build_transfer(sender, receiver, volume).await?;
+        SuiNetwork::ensure_dry_run_success(&dry_run)?;
+        let gas_budget = tx_data.gas_budget();
+
+        let outcome = self.sign_and_execute(signer, tx_data).await?;
+        tracing::info!(digest = %outcome.digest, gas_budget, "transaction executed");
+        Ok(outcome)
+    }
+
+    /// Builds an unsigned SUI transfer from `sender`, which does not need to be the
+    /// configured signer, with gas selected and estimated from its coins.
+    pub async fn build_transfer_tx(
+        &self,
+        sender: SuiAddress,
+        receiver: String,
+        volume: u64,
+    ) -> SuiChainResult<TransactionData> {
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let (tx_data, dry_run) = self.build_transfer(sender, receiver, volume).await?;
+        SuiNetwork::ensure_dry_run_success(&dry_run)?;
+        Ok(tx_data)
+    }
+
+    /// Simulates `transfer` without executing it.
+    #[tracing::instrument(skip(self), fields(sender = %self.get_my_address()))]
+    pub async fn dry_run_transfer(
+        &self,
+        receiver: String,
+        volume: u64,
+    ) -> SuiChainResult<DryRunPreview> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let (tx_data, dry_run) = self
+            .build_transfer(signer.address(), receiver, volume)
+            .await?;
+        Ok(SuiNetwork::dry_run_preview(&tx_data, dry_run))
+    }
+
+    async fn build_transfer(
+        &self,
+        sender: SuiAddress,
+        receiver: SuiAddress,
+        volume: u64,
+    ) -> SuiChainResult<(TransactionData, DryRunTransactionBlockResponse)> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        ptb.pay_sui(vec![receiver], vec![volume])
+            .map_err(|e| SuiChainError::TransactionBuild(e.to_string()))?;
+        self.prepare_transaction(sender, ptb.finish(), volume, &[])
             .await
-            .unwrap();
+    }
 
-        let tx_data = TransactionData::new_transfer_sui(
-            receiver,
-            self.public_addr_obj.unwrap().clone(),
-            Some(volume),
-            gas_coin.object_ref(),
-            gas_budget,
-            gas_price,
-        );
-        let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data.clone());
-        let raw_tx = bcs::to_bytes(&intent_msg.clone()).expect("bcs should not fail");
+    /// Sends `amount` base units of any coin type, merging as many coin objects of
+    /// that type as needed. Gas is always paid from separate SUI coins.
+    #[tracing::instrument(skip(self), fields(sender = %self.get_my_address()))]
+    pub async fn transfer_coin(
+        &self,
+        receiver: String,
+        coin_type: String,
+        amount: u64,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let (tx_data, dry_run) = self
+            .build_transfer_coin(signer.address(), receiver, coin_type, amount)
+            .await?;
+        SuiNetwork::ensure_dry_run_success(&dry_run)?;
+        let gas_budget = tx_data.gas_budget();
 
-        let mut hasher = Blake2b256::new();
-        hasher.update(raw_tx.clone());
+        let outcome = self.sign_and_execute(signer, tx_data).await?;
+        tracing::info!(digest = %outcome.digest, gas_budget, "transaction executed");
+        Ok(outcome)
+    }
 
-        let sui_sig = key_pair.sign(&hasher.finalize().to_vec());
-        let res = sui_sig.verify_secure(
-            &intent_msg.clone(),
-            self.public_addr_obj.unwrap(),
-            sui_types::crypto::SignatureScheme::ED25519,
-        );
-        if res.is_err() {
-            return Err(anyhow::Error::msg("verify_secure is ERR".to_string()));
-        }
-        let transaction_response = sui_client
-            .quorum_driver_api()
-            .execute_transaction_block(
-                sui_types::transaction::Transaction::from_generic_sig_data(
-                    intent_msg.value.clone(),
-                    vec![GenericSignature::Signature(sui_sig.clone())],
-                ),
-                SuiTransactionBlockResponseOptions::default(),
-                None,
-            )
-            .await;
-        match transaction_response {
-            Ok(transaction_response) => {
-                println!("gas_coin: {:?}", gas_coin.clone());
-                println!("---------------------------------------");
-
-                println!("gas_price: {:?}", gas_price.clone());
-                println!("---------------------------------------");
-
-                println!("tx_data: {:?}", tx_data.clone());
-                println!("---------------------------------------");
-
-                println!("intent_msg: {:?}", intent_msg.clone());
-                println!("---------------------------------------");
-
-                println!("transaction_response: {:?}", transaction_response.clone());
-                println!("---------------------------------------");
-                let tx_digest = transaction_response.digest.base58_encode();
-                println!(
-                    "Transaction executed. Transaction digest: {}",
-                    tx_digest.clone()
-                );
-                println!("---------------------------------------");
-                println!("{transaction_response}");
-                Ok(tx_digest)
-            }
-            Err(tx_err) => Err(anyhow::Error::msg(tx_err.to_string())),
-        }
+    /// Builds an unsigned `transfer_coin` transaction from `sender`.
+    pub async fn build_transfer_coin_tx(
+        &self,
+        sender: SuiAddress,
+        receiver: String,
+        coin_type: String,
+        amount: u64,
+    ) -> SuiChainResult<TransactionData> {
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let (tx_data, dry_run) = self
+            .build_transfer_coin(sender, receiver, coin_type, amount)
+            .await?;
+        SuiNetwork::ensure_dry_run_success(&dry_run)?;
+        Ok(tx_data)
     }
 
-    pub fn get_my_address(&self) -> String {
-        self.public_addr_str.clone()
+    /// Builds an unsigned `transfer_coin` transaction from `sender` whose gas is
+    /// owned and paid by `sponsor`. To move SUI, pass `0x2::sui::SUI` as the coin
+    /// type: it is taken from the sender coins, never from the sponsor gas.
+    pub async fn build_sponsored_transfer_coin_tx(
+        &self,
+        sender: SuiAddress,
+        sponsor: SuiAddress,
+        receiver: String,
+        coin_type: String,
+        amount: u64,
+    ) -> SuiChainResult<TransactionData> {
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let (pt, coin_ids) = self
+            .transfer_coin_commands(sender, receiver, coin_type, amount)
+            .await?;
+        let (tx_data, dry_run) = self
+            .prepare_sponsored_transaction(sender, sponsor, pt, &coin_ids)
+            .await?;
+        SuiNetwork::ensure_dry_run_success(&dry_run)?;
+        Ok(tx_data)
     }
-    pub fn set_my_private_key(&mut self, private_key: String) -> bool {
-        self.public_addr_str = "".to_string();
-        match BASE64_STANDARD.decode(private_key) {
-            Ok(income_64) => match SuiKeyPair::from_bytes(&income_64) {
-                Ok(key_pair) => {
-                    self.key_pair = Some(key_pair.copy());
-                    self.public_addr_obj = Some(SuiAddress::from(&key_pair.public()));
-                    self.public_addr_str = self.public_addr_obj.unwrap().to_string();
-                    println!("address (hex)   : {}", self.public_addr_str.clone());
-                    true
-                }
-                Err(_) => false,
+
+    /// `transfer_coin` with the gas paid by `sponsor`, which co-signs the transaction.
+    #[tracing::instrument(skip(self, sponsor), fields(sender = %self.get_my_address()))]
+    pub async fn transfer_coin_sponsored(
+        &self,
+        receiver: String,
+        coin_type: String,
+        amount: u64,
+        sponsor: &dyn TransactionSigner,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let sender = self.signer_address()?;
+        let sponsor_address = sponsor.address();
+        let tx_data = self
+            .build_sponsored_transfer_coin_tx(sender, sponsor_address, receiver, coin_type, amount)
+            .await?;
+        self.execute_sponsored(tx_data, sponsor).await
+    }
+
+    /// Simulates `transfer_coin` without executing it.
+    #[tracing::instrument(skip(self), fields(sender = %self.get_my_address()))]
+    pub async fn dry_run_transfer_coin(
+        &self,
+        receiver: String,
+        coin_type: String,
+        amount: u64,
+    ) -> SuiChainResult<DryRunPreview> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        let receiver = SuiNetwork::string_to_address_object(receiver)?;
+        let (tx_data, dry_run) = self
+            .build_transfer_coin(signer.address(), receiver, coin_type, amount)
+            .await?;
+        Ok(SuiNetwork::dry_run_preview(&tx_data, dry_run))
+    }
+
+    async fn build_transfer_coin(
+        &self,
+        sender: SuiAddress,
+        receiver: SuiAddress,
+        coin_type: String,
+        amount: u64,
+    ) -> SuiChainResult<(TransactionData, DryRunTransactionBlockResponse)> {
+        let (pt, coin_ids) = self
+            .transfer_coin_commands(sender, receiver, coin_type, amount)
+            .await?;
+        self.prepare_transaction(sender, pt, 0, &coin_ids).await
+    }
+
+    /// Pays `amount` from coins of `sender`, returning the commands and the coins used.
+    async fn transfer_coin_commands(
+        &self,
+        sender: SuiAddress,
+        receiver: SuiAddress,
+        coin_type: String,
+        amount: u64,
+    ) -> SuiChainResult<(ProgrammableTransaction, Vec<ObjectID>)> {
+        let coins = self.select_coins(sender, coin_type, amount as u128).await?;
+        let coin_ids: Vec<ObjectID> = coins.iter().map(|coin| coin.coin_object_id).collect();
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        ptb.pay(
+            coins.iter().map(|coin| coin.object_ref()).collect(),
+            vec![receiver],
+            vec![amount],
+        )
+        .map_err(|e| SuiChainError::TransactionBuild(e.to_string()))?;
+        Ok((ptb.finish(), coin_ids))
+    }
+
+    /// Coins of `coin_type` owned by `address` that together cover `amount`.
+    async fn select_coins(
+        &self,
+        address: SuiAddress,
+        coin_type: String,
+        amount: u128,
+    ) -> SuiChainResult<Vec<Coin>> {
+        self.read(|client| {
+            let coin_type = coin_type.clone();
+            async move {
+                client
+                    .coin_read_api()
+                    .select_coins(address, Some(coin_type), amount, vec![])
+                    .await
+            }
+        })
+        .await
+        .map_err(|error| match error {
+            SuiChainError::Rpc {
+                source: sui_sdk::error::Error::InsufficientFund { address, amount },
+            } => SuiChainError::InsufficientBalance {
+                address,
+                coin_type,
+                amount,
             },
-            Err(_) => false,
-        }
+            other => other,
+        })
     }
-    pub fn string_to_address_object(address_str: String) -> Result<SuiAddress, anyhow::Error> {
-        match SuiAddress::from_str(&address_str) {
-            Ok(addr) => Ok(addr),
-            Err(e) => Err(e),
+
+    /// Pays every recipient in as few transactions as possible. Payments are split
+    /// into chunks of `BATCH_PAY_CHUNK_SIZE`, each executed as one transaction block.
+    /// A failed chunk does not stop the following ones, so check every result.
+    /// Several chunks all go to one endpoint, without failing over.
+    #[tracing::instrument(skip(self, payments), fields(payments = payments.len()))]
+    pub async fn batch_pay(&self, payments: Vec<Payment>) -> SuiChainResult<Vec<BatchChunk>> {
+        let sender = self.signer_address()?;
+        let recipients = SuiNetwork::payment_recipients(&payments)?;
+        // Later chunks select coins right after the previous chunk. Send every chunk
+        // to one endpoint and wait until it has applied the effects, so it reports
+        // the new coin versions.
+        let network = if payments.len() > BATCH_PAY_CHUNK_SIZE {
+            let mut config = (*self.config).clone();
+            if config.wait_mode == WaitMode::Effects {
+                config.wait_mode = WaitMode::LocalExecution;
+            }
+            SuiNetwork {
+                config: Arc::new(config),
+                pool: Arc::new(self.pool.pinned()?),
+                signer: self.signer.clone(),
+            }
+        } else {
+            self.clone()
+        };
+
+        let mut chunks = Vec::new();
+        for (start, end) in SuiNetwork::batch_chunks(payments.len()) {
+            let result = match network
+                .pay_chunk(sender, &payments[start..end], &recipients[start..end])
+                .await
+            {
+                Ok(builder) => builder.execute().await,
+                Err(error) => Err(error),
+            };
+            match &result {
+                Ok(outcome) => {
+                    tracing::info!(digest = %outcome.digest, start, end, "batch chunk paid")
+                }
+                Err(error) => tracing::warn!(%error, start, end, "batch chunk failed"),
+            }
+            chunks.push(BatchChunk {
+                payments: start..end,
+                result,
+            });
         }
+        Ok(chunks)
     }
 
-    pub async fn get_latest_checkpoint_no(&mut self) -> Result<u64, anyhow::Error> {
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
-        }
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
-        let result = sui_client.read_api().get_total_transaction_blocks().await;
-        if result.is_ok() {
-            return Ok(result.unwrap());
+    /// Simulates `batch_pay` chunk by chunk. Every chunk is simulated against the
+    /// current state, as if the chunks before it had not been paid.
+    #[tracing::instrument(skip(self, payments), fields(payments = payments.len()))]
+    pub async fn dry_run_batch_pay(
+        &self,
+        payments: Vec<Payment>,
+    ) -> SuiChainResult<Vec<BatchChunkPreview>> {
+        let sender = self.signer_address()?;
+        let recipients = SuiNetwork::payment_recipients(&payments)?;
+        let mut chunks = Vec::new();
+        for (start, end) in SuiNetwork::batch_chunks(payments.len()) {
+            let result = match self
+                .pay_chunk(sender, &payments[start..end], &recipients[start..end])
+                .await
+            {
+                Ok(builder) => builder.dry_run().await,
+                Err(error) => Err(error),
+            };
+            chunks.push(BatchChunkPreview {
+                payments: start..end,
+                result,
+            });
         }
-        return Err(anyhow::Error::msg("could-not-read"));
+        Ok(chunks)
+    }
+
+    fn payment_recipients(payments: &[Payment]) -> SuiChainResult<Vec<SuiAddress>> {
+        payments
+            .iter()
+            .map(|payment| SuiNetwork::string_to_address_object(payment.recipient.clone()))
+            .collect()
+    }
+
+    fn batch_chunks(count: usize) -> Vec<(usize, usize)> {
+        (0..count)
+            .step_by(BATCH_PAY_CHUNK_SIZE)
+            .map(|start| (start, (start + BATCH_PAY_CHUNK_SIZE).min(count)))
+            .collect()
     }
-    pub async fn get_checkpoint(&mut self, seq_no: u64) -> Result<Vec<String>, anyhow::Error> {
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
+
+    /// Builds the transaction block paying one chunk.
+    async fn pay_chunk(
+        &self,
+        sender: SuiAddress,
+        payments: &[Payment],
+        recipients: &[SuiAddress],
+    ) -> SuiChainResult<TransactionBlockBuilder> {
+        let mut coin_types: Vec<&String> = Vec::new();
+        for payment in payments {
+            if !coin_types.contains(&&payment.coin_type) {
+                coin_types.push(&payment.coin_type);
+            }
         }
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
-        let seq_no: BigInt<u64> = seq_no.into();
-        let checkpoint_result = sui_client
-            .read_api()
-            .get_checkpoints(Some(seq_no), Some(1), false)
-            .await;
-        match checkpoint_result {
-            Ok(checkpoint_data) => {
-                // checkpoint_data.clone()
-                dbg!(checkpoint_data.clone());
-                // for item in checkpoint_data.data.iter(){
-                //     // dbg!(item.)
-                // }
-                // dbg!(checkpoint_result);
-                return Ok(Vec::new());
+
+        let mut builder = self.transaction_builder();
+        for coin_type in coin_types {
+            let indexes: Vec<usize> = (0..payments.len())
+                .filter(|index| &payments[*index].coin_type == coin_type)
+                .collect();
+            let amounts: Vec<u64> = indexes
+                .iter()
+                .map(|index| payments[*index].amount)
+                .collect();
+
+            let source = if is_sui_coin_type(coin_type) {
+                builder.gas()
+            } else {
+                let total: u128 = amounts.iter().map(|amount| *amount as u128).sum();
+                let coins = self.select_coins(sender, coin_type.clone(), total).await?;
+                let mut coin_args = coins
+                    .iter()
+                    .map(|coin| builder.owned_object(coin.object_ref()))
+                    .collect::<SuiChainResult<Vec<Argument>>>()?;
+                if coin_args.is_empty() {
+                    return Err(SuiChainError::NotFound(format!("{} coins", coin_type)));
+                }
+                let target = coin_args.remove(0);
+                for sources in coin_args.chunks(MAX_MERGE_SOURCES) {
+                    builder.merge_coins(target, sources.to_vec());
+                }
+                target
+            };
+            let split = builder.split_coins(source, amounts)?;
+            for (coin, index) in split.into_iter().zip(indexes) {
+                builder.transfer_objects(vec![coin], recipients[index])?;
             }
-            Err(_) => {
-                return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
+        }
+        Ok(builder)
+    }
+
+    /// Simulates any transaction. The preview history is rendered for its sender.
+    pub async fn dry_run(&self, tx_data: TransactionData) -> SuiChainResult<DryRunPreview> {
+        let dry_run = self
+            .read(|client| {
+                let tx_data = tx_data.clone();
+                async move { client.read_api().dry_run_transaction_block(tx_data).await }
+            })
+            .await?;
+        Ok(SuiNetwork::dry_run_preview(&tx_data, dry_run))
+    }
+
+    /// Runs `pt` in dev-inspect mode: nothing is charged or checked for gas, and the
+    /// return values of every command are reported. Useful for read-only Move calls.
+    pub async fn dev_inspect(
+        &self,
+        sender: SuiAddress,
+        pt: ProgrammableTransaction,
+    ) -> SuiChainResult<DevInspectResults> {
+        self.read(|client| {
+            let tx_kind = TransactionKind::ProgrammableTransaction(pt.clone());
+            async move {
+                client
+                    .read_api()
+                    .dev_inspect_transaction_block(sender, tx_kind, None, None, None)
+                    .await
             }
+        })
+        .await
+    }
+
+    pub(crate) fn ensure_dry_run_success(
+        dry_run: &DryRunTransactionBlockResponse,
+    ) -> SuiChainResult<()> {
+        match dry_run.effects.status() {
+            SuiExecutionStatus::Success => Ok(()),
+            SuiExecutionStatus::Failure { error } => Err(SuiChainError::ExecutionFailed {
+                digest: None,
+                status: error.clone(),
+            }),
         }
     }
-    pub async fn get_block_detail(&mut self, seq_no: u64) -> Result<Vec<String>, anyhow::Error> {
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
+
+    pub(crate) fn dry_run_preview(
+        tx_data: &TransactionData,
+        dry_run: DryRunTransactionBlockResponse,
+    ) -> DryRunPreview {
+        let (success, status) = match dry_run.effects.status() {
+            SuiExecutionStatus::Success => (true, "success".to_string()),
+            SuiExecutionStatus::Failure { error } => (false, error.clone()),
+        };
+        let gas_cost = dry_run.effects.gas_cost_summary().clone();
+
+        // Shape the simulation like an executed transaction so the history
+        // is rendered by the same rules as the wallet history.
+        let digest = *dry_run.effects.transaction_digest();
+        let mut tx_details = SuiTransactionBlockResponse::new(digest);
+        tx_details.transaction = Some(SuiTransactionBlock {
+            data: dry_run.input,
+            tx_signatures: vec![],
+        });
+        tx_details.effects = Some(dry_run.effects.clone());
+        tx_details.events = Some(dry_run.events);
+        tx_details.object_changes = Some(dry_run.object_changes);
+        tx_details.balance_changes = Some(dry_run.balance_changes.clone());
+        let history = SuiNetwork::organize_balance_list(tx_details, tx_data.sender());
+
+        DryRunPreview {
+            success,
+            status,
+            gas_budget: tx_data.gas_budget(),
+            computation_cost: gas_cost.computation_cost,
+            storage_cost: gas_cost.storage_cost,
+            storage_rebate: gas_cost.storage_rebate,
+            net_gas_cost: gas_cost.net_gas_usage(),
+            balance_changes: dry_run.balance_changes,
+            effects: dry_run.effects,
+            history,
         }
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
-        let control_no: BigInt<u64> = seq_no.into();
-        match sui_client
-            .read_api()
-            .get_checkpoints(Some(control_no), Some(1), false)
+    }
+
+    /// SUI coins of `address` with a non-zero balance, largest first.
+    pub async fn get_sorted_gas_coins(
+        &self,
+        address: SuiAddress,
+    ) -> SuiChainResult<Vec<(SuiObjectData, u64)>> {
+        self.read(|client| async move { fetch_sorted_gas_coins(&client, &address).await })
             .await
-        {
-            Ok(checkpoint_data) => {
-                let mut tx_list = Vec::new();
-                for c_item in checkpoint_data.data.iter() {
-                    for t_time in c_item.transactions.iter() {
-                        tx_list.push(t_time.base58_encode());
-                    }
+    }
+
+    /// Picks gas coins and a gas budget for `pt`. `spend` is the SUI amount the
+    /// commands take from the gas coin, coins in `exclude` are never used for gas.
+    /// The budget is the dry-run cost plus the configured safety margin; the dry
+    /// run is returned as well and may report a failed execution.
+    pub(crate) async fn prepare_transaction(
+        &self,
+        sender: SuiAddress,
+        pt: ProgrammableTransaction,
+        spend: u64,
+        exclude: &[ObjectID],
+    ) -> SuiChainResult<(TransactionData, DryRunTransactionBlockResponse)> {
+        self.prepare_with_gas_owner(sender, sender, pt, spend, exclude)
+            .await
+    }
+
+    /// Like `prepare_transaction`, but gas is paid by `sponsor`. The commands must
+    /// not take SUI from the gas coin, which belongs to the sponsor.
+    pub(crate) async fn prepare_sponsored_transaction(
+        &self,
+        sender: SuiAddress,
+        sponsor: SuiAddress,
+        pt: ProgrammableTransaction,
+        exclude: &[ObjectID],
+    ) -> SuiChainResult<(TransactionData, DryRunTransactionBlockResponse)> {
+        self.prepare_with_gas_owner(sender, sponsor, pt, 0, exclude)
+            .await
+    }
+
+    async fn prepare_with_gas_owner(
+        &self,
+        sender: SuiAddress,
+        gas_owner: SuiAddress,
+        pt: ProgrammableTransaction,
+        spend: u64,
+        exclude: &[ObjectID],
+    ) -> SuiChainResult<(TransactionData, DryRunTransactionBlockResponse)> {
+        let gas_coins: Vec<(SuiObjectData, u64)> = self
+            .get_sorted_gas_coins(gas_owner)
+            .await?
+            .into_iter()
+            .filter(|(object, _)| !exclude.contains(&object.object_id))
+            .collect();
+        let gas_price = self
+            .read(|client| async move { client.read_api().get_reference_gas_price().await })
+            .await?;
+        let insufficient = |required: u64| {
+            if spend == 0 {
+                SuiChainError::InsufficientGas { address: gas_owner }
+            } else {
+                SuiChainError::InsufficientBalance {
+                    address: gas_owner,
+                    coin_type: SUI_COIN_TYPE.to_string(),
+                    amount: required as u128,
                 }
-                return Ok(tx_list);
             }
-            Err(_) => {
-                return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
+        };
+
+        // Dry run with as much budget as the gas coins that can be used at once
+        // afford, capped.
+        let available: u64 = gas_coins
+            .iter()
+            .take(MAX_GAS_COINS)
+            .map(|(_, balance)| *balance)
+            .sum();
+        let dry_run_budget = available.saturating_sub(spend).min(MAX_DRY_RUN_GAS_BUDGET);
+        if dry_run_budget == 0 {
+            return Err(insufficient(spend.saturating_add(1)));
+        }
+        let dry_run_coins = select_gas_coins(&gas_coins, spend + dry_run_budget)
+            .ok_or_else(|| insufficient(spend + dry_run_budget))?;
+        let build = |gas_payment: Vec<ObjectRef>, pt: ProgrammableTransaction, gas_budget: u64| {
+            if gas_owner == sender {
+                TransactionData::new_programmable(sender, gas_payment, pt, gas_budget, gas_price)
+            } else {
+                TransactionData::new_programmable_allow_sponsor(
+                    sender,
+                    gas_payment,
+                    pt,
+                    gas_budget,
+                    gas_price,
+                    gas_owner,
+                )
             }
+        };
+        let dry_run_data = build(dry_run_coins, pt.clone(), dry_run_budget);
+        let dry_run = self
+            .read(|client| {
+                let dry_run_data = dry_run_data.clone();
+                async move {
+                    client
+                        .read_api()
+                        .dry_run_transaction_block(dry_run_data)
+                        .await
+                }
+            })
+            .await?;
+
+        let gas_cost = dry_run.effects.gas_cost_summary();
+        let estimate = gas_cost.computation_cost + gas_cost.storage_cost;
+        let gas_budget = (estimate as f64 * (1.0 + self.config.gas_safety_margin)).ceil() as u64;
+        let required = spend.saturating_add(gas_budget);
+        let gas_payment =
+            select_gas_coins(&gas_coins, required).ok_or_else(|| insufficient(required))?;
+        tracing::debug!(
+            estimate,
+            gas_budget,
+            gas_coins = gas_payment.len(),
+            "gas selected"
+        );
+        Ok((build(gas_payment, pt, gas_budget), dry_run))
+    }
+
+    /// Signs `tx_data` with the configured key pair and executes it.
+    pub(crate) async fn execute_transaction_data(
+        &self,
+        tx_data: TransactionData,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        self.sign_and_execute(signer, tx_data).await
+    }
+
+    async fn sign_and_execute(
+        &self,
+        signer: &Arc<dyn TransactionSigner>,
+        tx_data: TransactionData,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let signature = sign_transaction(signer.as_ref(), &tx_data).await?;
+        tracing::trace!(?tx_data);
+        self.execute_signed(tx_data, vec![GenericSignature::Signature(signature)])
+            .await
+    }
+
+    /// Signs a sponsored transaction with the configured signer as sender and
+    /// `sponsor` as gas owner, then executes it.
+    pub async fn execute_sponsored(
+        &self,
+        tx_data: TransactionData,
+        sponsor: &dyn TransactionSigner,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        if tx_data.sender() != signer.address() {
+            return Err(SuiChainError::Signature(format!(
+                "the configured signer {} can not sign for the sender {}",
+                signer.address(),
+                tx_data.sender()
+            )));
+        }
+        if tx_data.gas_owner() != sponsor.address() {
+            return Err(SuiChainError::Signature(format!(
+                "gas is owned by {}, not by the sponsor {}",
+                tx_data.gas_owner(),
+                sponsor.address()
+            )));
         }
+        let sender_signature = sign_transaction(signer.as_ref(), &tx_data).await?;
+        let sponsor_signature = sign_transaction(sponsor, &tx_data).await?;
+        self.execute_signed(
+            tx_data,
+            vec![
+                GenericSignature::Signature(sender_signature),
+                GenericSignature::Signature(sponsor_signature),
+            ],
+        )
+        .await
     }
-    pub async fn get_transaction_detail(
+
+    /// Executes `tx_data` with signatures collected elsewhere, e.g. a combined
+    /// multisig or the signatures of a sender and a gas sponsor.
+    pub async fn execute_signed(
+        &self,
+        tx_data: TransactionData,
+        signatures: Vec<GenericSignature>,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let transaction = Transaction::from_generic_sig_data(tx_data, signatures);
+        self.submit(transaction).await
+    }
+
+    /// Submits a transaction exported with `transaction_to_base64` together with
+    /// signatures from `sign_transaction_offline`.
+    pub async fn submit_signed_base64(
+        &self,
+        tx_bytes: String,
+        signatures: Vec<String>,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let tx_data = transaction_from_base64(&tx_bytes)?;
+        let signatures = signatures
+            .iter()
+            .map(|signature| signature_from_base64(signature))
+            .collect::<SuiChainResult<Vec<GenericSignature>>>()?;
+        self.execute_signed(tx_data, signatures).await
+    }
+
+    /// Executes a signed transaction and waits as configured by `WaitMode`.
+    /// A transaction that was executed but failed is returned as `ExecutionFailed`.
+    pub(crate) async fn submit(
+        &self,
+        transaction: Transaction,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let request_type = match self.config.wait_mode {
+            WaitMode::Effects => ExecuteTransactionRequestType::WaitForEffectsCert,
+            WaitMode::LocalExecution | WaitMode::Checkpoint { .. } => {
+                ExecuteTransactionRequestType::WaitForLocalExecution
+            }
+        };
+        let mut transaction_response = self
+            .pool
+            .call(|client| {
+                let transaction = transaction.clone();
+                async move {
+                    client
+                        .quorum_driver_api()
+                        .execute_transaction_block(
+                            transaction,
+                            SuiNetwork::outcome_options(),
+                            Some(request_type.clone()),
+                        )
+                        .await
+                }
+            })
+            .await?;
+        tracing::trace!(?transaction_response);
+
+        if let WaitMode::Checkpoint {
+            timeout,
+            poll_interval,
+        } = self.config.wait_mode
+        {
+            let digest = transaction_response.digest;
+            let started = Instant::now();
+            while transaction_response.checkpoint.is_none() {
+                if started.elapsed() >= timeout {
+                    return Err(SuiChainError::CheckpointTimeout {
+                        digest: digest.base58_encode(),
+                    });
+                }
+                tokio::time::sleep(poll_interval).await;
+                match self
+                    .pool
+                    .call(|client| async move {
+                        client
+                            .read_api()
+                            .get_transaction_with_options(digest, SuiNetwork::outcome_options())
+                            .await
+                    })
+                    .await
+                {
+                    Ok(response) => transaction_response = response,
+                    Err(error) => tracing::debug!(%digest, %error, "waiting for checkpoint"),
+                }
+            }
+        }
+
+        let outcome = SuiNetwork::execution_outcome(transaction_response)?;
+        if !outcome.success {
+            return Err(SuiChainError::ExecutionFailed {
+                digest: Some(outcome.digest),
+                status: outcome.status,
+            });
+        }
+        Ok(outcome)
+    }
+
+    fn outcome_options() -> SuiTransactionBlockResponseOptions {
+        SuiTransactionBlockResponseOptions::new()
+            .with_effects()
+            .with_balance_changes()
+    }
+
+    fn execution_outcome(
+        transaction_response: SuiTransactionBlockResponse,
+    ) -> SuiChainResult<ExecutionOutcome> {
+        let digest = transaction_response.digest.base58_encode();
+        let effects = transaction_response
+            .effects
+            .ok_or_else(|| SuiChainError::NotFound(format!("effects of {}", digest)))?;
+        let (success, status) = match effects.status() {
+            SuiExecutionStatus::Success => (true, "success".to_string()),
+            SuiExecutionStatus::Failure { error } => (false, error.clone()),
+        };
+        let gas_cost = effects.gas_cost_summary();
+        Ok(ExecutionOutcome {
+            digest,
+            success,
+            status,
+            computation_cost: gas_cost.computation_cost,
+            storage_cost: gas_cost.storage_cost,
+            storage_rebate: gas_cost.storage_rebate,
+            gas_used: gas_cost.net_gas_usage(),
+            created: effects.created().to_vec(),
+            mutated: effects.mutated().to_vec(),
+            balance_changes: transaction_response.balance_changes.unwrap_or_default(),
+            checkpoint: transaction_response.checkpoint,
+            timestamp_ms: transaction_response.timestamp_ms,
+        })
+    }
+
+    pub(crate) fn signer_address(&self) -> SuiChainResult<SuiAddress> {
+        match &self.signer {
+            Some(signer) => Ok(signer.address()),
+            None => Err(SuiChainError::NoSigner),
+        }
+    }
+
+    /// Starts a programmable transaction block signed by the configured key pair.
+    pub fn transaction_builder(&self) -> TransactionBlockBuilder {
+        TransactionBlockBuilder::new(self.clone())
+    }
+
+    pub fn get_my_address(&self) -> String {
+        match &self.signer {
+            Some(signer) => signer.address().to_string(),
+            None => "".to_string(),
+        }
+    }
+    /// Accepts a Bech32 `suiprivkey1...` string or base64 flag-prefixed key bytes.
+    pub fn set_my_private_key(&mut self, private_key: String) -> SuiChainResult<()> {
+        self.signer = None;
+        let key_pair = parse_private_key(&private_key)?;
+        self.set_my_key_pair(key_pair);
+        Ok(())
+    }
+    /// Uses the key derived from a BIP-39 mnemonic, see `mnemonic_to_keypair`.
+    pub fn set_my_mnemonic(
         &mut self,
-        tx_digest: String,
-    ) -> Result<SuiTransactionBlockResponse, anyhow::Error> {
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
+        phrase: String,
+        scheme: SignatureScheme,
+        derivation_path: Option<String>,
+    ) -> SuiChainResult<()> {
+        self.signer = None;
+        let key_pair = mnemonic_to_keypair(&phrase, scheme, derivation_path.as_deref())?;
+        self.set_my_key_pair(key_pair);
+        Ok(())
+    }
+    pub fn set_my_key_pair(&mut self, key_pair: SuiKeyPair) {
+        self.set_my_signer(Arc::new(KeyPairSigner::new(key_pair)));
+    }
+    /// Signs write operations with `signer`, e.g. a `RemoteSigner`.
+    pub fn set_my_signer(&mut self, signer: Arc<dyn TransactionSigner>) {
+        tracing::debug!(address = %signer.address(), scheme = %signer.scheme(), "signer assigned");
+        self.signer = Some(signer);
+    }
+    /// Uses a key of `keystore` as the signer, selected by alias or address.
+    pub fn set_my_keystore_key(
+        &mut self,
+        keystore: &SuiKeystore,
+        alias_or_address: &str,
+    ) -> SuiChainResult<()> {
+        self.signer = None;
+        let key_pair = keystore.get(alias_or_address)?.copy();
+        self.set_my_key_pair(key_pair);
+        Ok(())
+    }
+    /// Exports the signer key as a Bech32 `suiprivkey1...` string. Only possible
+    /// when the key is held in memory.
+    pub fn export_my_private_key(&self) -> SuiChainResult<String> {
+        let signer = self.signer.as_ref().ok_or(SuiChainError::NoSigner)?;
+        match signer.key_pair() {
+            Some(key_pair) => keypair_to_bech32(key_pair),
+            None => Err(SuiChainError::Signature(
+                "the signer does not expose its private key".to_string(),
+            )),
         }
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
+    }
+    /// Returns a handle sharing this connection but signing with another key.
+    pub fn with_private_key(&self, private_key: String) -> SuiChainResult<SuiNetwork> {
+        let mut network = self.clone();
+        network.set_my_private_key(private_key)?;
+        Ok(network)
+    }
+    pub fn string_to_address_object(address_str: String) -> SuiChainResult<SuiAddress> {
+        SuiAddress::from_str(&address_str).map_err(|_| SuiChainError::InvalidAddress(address_str))
+    }
 
-        match TransactionDigest::from_str(&tx_digest) {
-            Ok(tx_digest) => {
-                match sui_client
+    pub async fn get_latest_checkpoint_no(&self) -> SuiChainResult<u64> {
+        self.read(|client| async move {
+            client
+                .read_api()
+                .get_latest_checkpoint_sequence_number()
+                .await
+        })
+        .await
+    }
+    #[tracing::instrument(skip(self))]
+    pub async fn get_checkpoint(&self, seq_no: u64) -> SuiChainResult<CheckpointSummary> {
+        let checkpoint = self
+            .read(|client| async move {
+                client
                     .read_api()
-                    .get_transaction_with_options(
-                        tx_digest,
-                        SuiTransactionBlockResponseOptions {
-                            show_input: true,
-                            show_raw_input: true,
-                            show_effects: true,
-                            show_events: true,
-                            show_object_changes: true,
-                            show_balance_changes: true,
-                            show_raw_effects: true,
-                        },
-                    )
+                    .get_checkpoint(CheckpointId::SequenceNumber(seq_no))
                     .await
-                {
-                    Ok(tx_detail_result) => Ok(tx_detail_result),
-                    Err(_) => {
-                        return Err(anyhow::Error::msg("balance-reading-error"));
-                    }
-                }
+            })
+            .await?;
+        tracing::trace!(?checkpoint);
+        Ok(CheckpointSummary::from(checkpoint))
+    }
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_detail(&self, seq_no: u64) -> SuiChainResult<Vec<String>> {
+        let checkpoint = self.get_checkpoint(seq_no).await?;
+        Ok(checkpoint.transactions)
+    }
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transaction_detail(
+        &self,
+        tx_digest: String,
+    ) -> SuiChainResult<SuiTransactionBlockResponse> {
+        match TransactionDigest::from_str(&tx_digest) {
+            Ok(tx_digest) => {
+                self.read(|client| async move {
+                    client
+                        .read_api()
+                        .get_transaction_with_options(
+                            tx_digest,
+                            SuiTransactionBlockResponseOptions {
+                                show_input: true,
+                                show_raw_input: true,
+                                show_effects: true,
+                                show_events: true,
+                                show_object_changes: true,
+                                show_balance_changes: true,
+                                show_raw_effects: true,
+                            },
+                        )
+                        .await
+                })
+                .await
             }
             Err(_) => {
-                return Err(anyhow::Error::msg("tx-digest-convert-error"));
+                return Err(SuiChainError::InvalidDigest(tx_digest));
             }
         }
     }
+    #[tracing::instrument(skip(self))]
     pub async fn get_wallet_balance(
-        &mut self,
+        &self,
         wallet_address: SuiAddress,
-    ) -> Result<String, anyhow::Error> {
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
-        }
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
-
+    ) -> SuiChainResult<String> {
         // match sui_client.coin_read_api().get_balance(wallet_address,Some(coin_type)).await {
-        match sui_client
-            .coin_read_api()
-            .get_balance(wallet_address, None)
+        match self
+            .read(|client| async move {
+                client
+                    .coin_read_api()
+                    .get_balance(wallet_address, None)
+                    .await
+            })
             .await
         {
             Ok(total_balance) => {
-                println!("wallet balance: {:?}", total_balance);
+                tracing::debug!(?total_balance);
                 Ok(format!("wallet balance: {:?}", total_balance))
             }
-            Err(_) => {
-                return Err(anyhow::Error::msg("balance-reading-error"));
+            Err(e) => {
+                return Err(e);
             }
         }
     }
 
     pub async fn get_wallet_history_with_wallet_hex(
-        &mut self,
+        &self,
         wallet_address: String,
-    ) -> Option<Vec<AccountHistory>> {
-        match SuiNetwork::string_to_address_object(wallet_address.clone()) {
-            Ok(converted_wallet_addr) => {
-                match self.get_wallet_history(converted_wallet_addr.clone()).await {
-                    Ok(result_list) =>Some(result_list),
-                    Err(_) =>None,
-                }
-            },
-            Err(_) =>None,
-        }
+    ) -> SuiChainResult<ReadResult<Vec<AccountHistory>>> {
+        let converted_wallet_addr = SuiNetwork::string_to_address_object(wallet_address)?;
+        self.get_wallet_history(converted_wallet_addr).await
     }
 
 
@@ -328,34 +1046,52 @@ impl SuiNetwork {
     // bu fonksiyon henüz tamamlanmadı.!
     // bu fonksiyon henüz tamamlanmadı.!
     // bu fonksiyon henüz tamamlanmadı.!
+    #[tracing::instrument(skip(self, options))]
+    pub async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: SuiObjectDataOptions,
+    ) -> SuiChainResult<SuiObjectData> {
+        let response = self
+            .read(|client| {
+                let options = options.clone();
+                async move {
+                    client
+                        .read_api()
+                        .get_object_with_options(object_id, options)
+                        .await
+                }
+            })
+            .await?;
+        response
+            .data
+            .ok_or_else(|| SuiChainError::NotFound(format!("object {}", object_id)))
+    }
     // bu fonksiyon henüz tamamlanmadı.!
     // bu fonksiyon henüz tamamlanmadı.!
+    #[tracing::instrument(skip(self))]
     pub async fn get_wallet_object(
-        &mut self,
+        &self,
         wallet_address: String,
-    )->Option<Vec<SuiObjectData>>{
-        if self.sui_client.is_none() {
-            return None;
-            // return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
-        }
-        let wallet_addr_obj=SuiNetwork::string_to_address_object(wallet_address.clone());
-        if wallet_addr_obj.is_err(){
-            return None;
-        }
+    ) -> SuiChainResult<ReadResult<Vec<SuiObjectData>>> {
+        let wallet_addr_obj = SuiNetwork::string_to_address_object(wallet_address.clone())?;
 
         let mut result_list=Vec::new();
-        let wallet_addr_obj=wallet_addr_obj.unwrap();
+        let mut errors = Vec::new();
         let limit: usize = 20;
         let mut current_cursor = None;
         // let mut result_list = Vec::new();
         // let sui = SuiClientBuilder::default().build(node_addr).await.unwrap();
         // let key_pair=self.key_pair.as_mut().unwrap().borrow_mut().copy();
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
         let mut counter = 1;
         'inner_loop: loop {
-            match sui_client
-                .read_api()
-                .get_owned_objects(wallet_addr_obj, None, current_cursor, Some(limit))
+            match self
+                .read(|client| async move {
+                    client
+                        .read_api()
+                        .get_owned_objects(wallet_addr_obj, None, current_cursor, Some(limit))
+                        .await
+                })
                 .await
             {
                 Ok(owned_objects) => {
@@ -364,9 +1100,17 @@ impl SuiNetwork {
                     }
                     for object in owned_objects.data.iter() {
                         counter = counter + 1;
-                        let object_data = object.data.as_ref().unwrap_or_else(|| {
-                            panic!("No object data for this SuiObjectResponse {:?}", object)
-                        });
+                        let Some(object_data) = object.data.as_ref() else {
+                            // Keep paging past it, the cursor is the last object id read.
+                            if let Ok(object_id) = object.object_id() {
+                                current_cursor = Some(object_id);
+                            }
+                            errors.push(SuiChainError::NotFound(format!(
+                                "object data for {:?}",
+                                object
+                            )));
+                            continue;
+                        };
                         result_list.push(object_data.clone());
 
                         let object_id = object_data.object_id;
@@ -470,50 +1214,63 @@ impl SuiNetwork {
                         */
                     }
                 }
-                Err(_) => {
-                    println!("owned objects error");
-                    return Some(result_list);
+                Err(e) => {
+                    if current_cursor.is_none() {
+                        return Err(e);
+                    }
+                    errors.push(e);
+                    break 'inner_loop;
                 }
             }
         }
-        Some(result_list)
+        Ok(ReadResult {
+            data: result_list,
+            errors,
+        })
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn get_wallet_history(
-        &mut self,
+        &self,
         wallet_address: SuiAddress,
-    ) -> Result<Vec<AccountHistory>, anyhow::Error> {
-        if self.sui_client.is_none() {
-            return Err(anyhow::Error::msg("not-connected-to-SUI-node"));
-        }
-
+    ) -> SuiChainResult<ReadResult<Vec<AccountHistory>>> {
         let limit: usize = 20;
         let mut current_cursor = None;
         let mut result_list = Vec::new();
+        let mut errors = Vec::new();
         // let sui = SuiClientBuilder::default().build(node_addr).await.unwrap();
         // let key_pair=self.key_pair.as_mut().unwrap().borrow_mut().copy();
-        let sui_client = self.sui_client.as_mut().unwrap().borrow_mut();
-        let mut counter = 1;
         'inner_loop: loop {
-            match sui_client
-                .read_api()
-                .get_owned_objects(wallet_address, None, current_cursor, Some(limit))
+            match self
+                .read(|client| async move {
+                    client
+                        .read_api()
+                        .get_owned_objects(wallet_address, None, current_cursor, Some(limit))
+                        .await
+                })
                 .await
             {
                 Ok(owned_objects) => {
                     if owned_objects.data.len() == 0 {
-                        println!("End Of data");
+                        tracing::debug!(objects = result_list.len(), "end of owned objects");
                         break 'inner_loop;
                     }
                     for object in owned_objects.data.iter() {
-                        println!("counter: {}", counter);
-                        counter = counter + 1;
-                        let object_data = object.data.as_ref().unwrap_or_else(|| {
-                            panic!("No object data for this SuiObjectResponse {:?}", object)
-                        });
+                        let Some(object_data) = object.data.as_ref() else {
+                            // Keep paging past it, the cursor is the last object id read.
+                            if let Ok(object_id) = object.object_id() {
+                                current_cursor = Some(object_id);
+                            }
+                            errors.push(SuiChainError::NotFound(format!(
+                                "object data for {:?}",
+                                object
+                            )));
+                            continue;
+                        };
                         let object_id = object_data.object_id;
                         current_cursor = Some(object_id.clone());
                         let version = object_data.version;
+                        tracing::trace!(%object_id, %version, "reading object history");
                         let sui_data_options = SuiObjectDataOptions {
                             show_type: true,
                             show_owner: true,
@@ -523,13 +1280,20 @@ impl SuiNetwork {
                             show_bcs: true,
                             show_storage_rebate: true,
                         };
-                        let past_object = sui_client
-                            .read_api()
-                            .try_get_parsed_past_object(
-                                object_id,
-                                version,
-                                sui_data_options.clone(),
-                            )
+                        let past_object = self
+                            .read(|client| {
+                                let sui_data_options = sui_data_options.clone();
+                                async move {
+                                    client
+                                        .read_api()
+                                        .try_get_parsed_past_object(
+                                            object_id,
+                                            version,
+                                            sui_data_options,
+                                        )
+                                        .await
+                                }
+                            })
                             .await;
                         match past_object {
                             Ok(past_object) => {
@@ -537,20 +1301,24 @@ impl SuiNetwork {
                                     SuiPastObjectResponse::VersionFound(sui_object_data) => {
                                         match sui_object_data.previous_transaction {
                                             Some(prev_tx) => {
-                                                match sui_client
-                                                    .read_api()
-                                                    .get_transaction_with_options(
-                                                        prev_tx,
-                                                        SuiTransactionBlockResponseOptions {
-                                                            show_input: true,
-                                                            show_raw_input: true,
-                                                            show_effects: true,
-                                                            show_events: true,
-                                                            show_object_changes: true,
-                                                            show_balance_changes: true,
-                                                            show_raw_effects: true,
-                                                        },
-                                                    )
+                                                match self
+                                                    .read(|client| async move {
+                                                        client
+                                                            .read_api()
+                                                            .get_transaction_with_options(
+                                                                prev_tx,
+                                                                SuiTransactionBlockResponseOptions {
+                                                                    show_input: true,
+                                                                    show_raw_input: true,
+                                                                    show_effects: true,
+                                                                    show_events: true,
+                                                                    show_object_changes: true,
+                                                                    show_balance_changes: true,
+                                                                    show_raw_effects: true,
+                                                                },
+                                                            )
+                                                            .await
+                                                    })
                                                     .await
                                                 {
                                                     Ok(tx_result) => match tx_result.status_ok() {
@@ -564,56 +1332,74 @@ impl SuiNetwork {
                                                             }
                                                         }
                                                         None => {
-                                                            println!("status UNKNOWN");
+                                                            tracing::warn!(
+                                                                digest = %prev_tx,
+                                                                "transaction status unknown"
+                                                            );
                                                         }
                                                     },
-                                                    Err(_) => {
-                                                        println!("tx-error");
+                                                    Err(e) => {
+                                                        errors.push(e);
                                                     }
                                                 }
                                             }
                                             None => {
-                                                println!("tx-none");
+                                                tracing::trace!(
+                                                    %object_id,
+                                                    "object has no previous transaction"
+                                                );
                                             }
                                         };
                                     }
                                     SuiPastObjectResponse::ObjectNotExists(object_id) => {
-                                        dbg!(object_id);
+                                        tracing::debug!(%object_id, "object does not exist");
                                     }
                                     SuiPastObjectResponse::ObjectDeleted(sui_object_ref) => {
-                                        dbg!(sui_object_ref);
+                                        tracing::debug!(?sui_object_ref, "object deleted");
                                     }
                                     SuiPastObjectResponse::VersionNotFound(
                                         object_id,
                                         sequence_number,
                                     ) => {
-                                        dbg!(object_id);
-                                        dbg!(sequence_number);
+                                        tracing::debug!(
+                                            %object_id,
+                                            %sequence_number,
+                                            "object version not found"
+                                        );
                                     }
                                     SuiPastObjectResponse::VersionTooHigh {
                                         object_id,
                                         asked_version,
                                         latest_version,
                                     } => {
-                                        dbg!(object_id);
-                                        dbg!(asked_version);
-                                        dbg!(latest_version);
+                                        tracing::debug!(
+                                            %object_id,
+                                            %asked_version,
+                                            %latest_version,
+                                            "object version too high"
+                                        );
                                     }
                                 };
                             }
-                            Err(_) => {
-                                println!("past object error");
+                            Err(e) => {
+                                errors.push(e);
                             }
                         }
                     }
                 }
-                Err(_) => {
-                    println!("owned objects error");
-                    return Ok(result_list);
+                Err(e) => {
+                    if current_cursor.is_none() {
+                        return Err(e);
+                    }
+                    errors.push(e);
+                    break 'inner_loop;
                 }
             }
         }
-        Ok(result_list)
+        Ok(ReadResult {
+            data: result_list,
+            errors,
+        })
     }
 
     pub fn organize_balance_list(
@@ -688,7 +1474,7 @@ impl SuiNetwork {
                 {
                     for i_item in balance_list.iter() {
                         let commision_account =
-                            format!("{}", i_item.owner.get_owner_address().unwrap().to_string());
+                            sui_owner_address(&i_item.owner).unwrap_or_default();
                         if commision_account.eq(&my_sui_addr.clone()) == false {
                             let currency_name = sui_get_currency_name(
                                 i_item.coin_type.to_canonical_display(true).to_string(),
@@ -698,15 +1484,16 @@ impl SuiNetwork {
                                 let commision_volume = i_item.amount as u128;
                                 let mut gas_total = 0;
                                 for i_item in balance_list.iter() {
-                                    let account_text = format!(
-                                        "{}",
-                                        i_item.owner.get_owner_address().unwrap().to_string()
-                                    );
+                                    let account_text =
+                                        sui_owner_address(&i_item.owner).unwrap_or_default();
                                     if account_text.eq(chain_currency) == true {
                                         if i_item.amount < 0 {
                                             gas_total = i_item.amount.abs() as u128;
                                         } else {
-                                            println!("kontrol-noktasi-011");
+                                            tracing::trace!(
+                                                digest = %tx_digest,
+                                                "positive SUI change on gas account"
+                                            );
                                         }
                                     }
                                 }
@@ -771,13 +1558,15 @@ impl SuiNetwork {
                 {
                     let mut gas_total = 0;
                     for i_item in balance_list.iter() {
-                        let account_text =
-                            format!("{}", i_item.owner.get_owner_address().unwrap().to_string());
+                        let account_text = sui_owner_address(&i_item.owner).unwrap_or_default();
                         if account_text.eq(chain_currency) == true {
                             if i_item.amount < 0 {
                                 gas_total = i_item.amount.abs() as u128;
                             } else {
-                                println!("kontrol-noktasi-011");
+                                tracing::trace!(
+                                    digest = %tx_digest,
+                                    "positive SUI change on gas account"
+                                );
                             }
                         }
                     }
@@ -886,10 +1675,7 @@ impl SuiNetwork {
                     if income_currency.eq(chain_currency) == true {
                         for item in balance_list.iter() {
                             if item.amount > 0 {
-                                let receiver = format!(
-                                    "{}",
-                                    item.owner.get_owner_address().unwrap().to_string()
-                                );
+                                let receiver = sui_owner_address(&item.owner).unwrap_or_default();
                                 let currency = sui_get_currency_name(
                                     item.coin_type.to_canonical_display(true).to_string(),
                                 );
@@ -897,10 +1683,8 @@ impl SuiNetwork {
                                 let mut sender = String::new();
                                 for item in balance_list.iter() {
                                     if item.amount < 0 {
-                                        sender = format!(
-                                            "{}",
-                                            item.owner.get_owner_address().unwrap().to_string()
-                                        );
+                                        sender =
+                                            sui_owner_address(&item.owner).unwrap_or_default();
                                     }
                                 }
                                 if receiver.eq(&my_sui_addr.clone()) == true {
@@ -923,10 +1707,8 @@ impl SuiNetwork {
                         }
                     } else {
                         for outer_item in balance_list.iter() {
-                            let outer_owner_addr = format!(
-                                "{}",
-                                outer_item.owner.get_owner_address().unwrap().to_string()
-                            );
+                            let outer_owner_addr =
+                                sui_owner_address(&outer_item.owner).unwrap_or_default();
                             if outer_owner_addr.eq(&my_sui_addr.clone()) {
                                 if outer_item.amount > 0 {
                                     let receiver = outer_owner_addr.clone();
@@ -935,14 +1717,9 @@ impl SuiNetwork {
                                         outer_item.coin_type.to_canonical_display(true).to_string(),
                                     );
                                     for inner_item in balance_list.iter() {
-                                        let inner_owner_addr = format!(
-                                            "{}",
-                                            inner_item
-                                                .owner
-                                                .get_owner_address()
-                                                .unwrap()
-                                                .to_string()
-                                        );
+                                        let inner_owner_addr =
+                                            sui_owner_address(&inner_item.owner)
+                                                .unwrap_or_default();
                                         if inner_owner_addr.eq(&my_sui_addr.clone()) == false {
                                             let sender = inner_owner_addr.clone();
                                             result_list.push(AccountHistory::ReceiveToken(
@@ -964,9 +1741,13 @@ impl SuiNetwork {
                                         }
                                     }
                                 } else {
-                                    println!("tx_digest: {}", tx_digest.clone());
-                                    dbg!(balance_list.clone());
-                                    std::process::exit(99);
+                                    // Falls through and is reported as a complex transaction.
+                                    tracing::warn!(
+                                        digest = %tx_digest,
+                                        ?balance_list,
+                                        "unexpected balance change shape"
+                                    );
+                                    break;
                                 }
                             }
                         }
@@ -986,8 +1767,7 @@ impl SuiNetwork {
                             real_volume = volume;
                         }
                         if item.amount > 0 {
-                            receiver =
-                                format!("{}", item.owner.get_owner_address().unwrap().to_string());
+                            receiver = sui_owner_address(&item.owner).unwrap_or_default();
                         }
                     }
                     result_list.push(AccountHistory::SentCoin(CoinDetail {
@@ -1025,10 +1805,7 @@ impl SuiNetwork {
                                 token_currency = sui_get_currency_name(
                                     item.coin_type.to_canonical_display(true).to_string(),
                                 );
-                                receiver = format!(
-                                    "{}",
-                                    item.owner.get_owner_address().unwrap().to_string()
-                                );
+                                receiver = sui_owner_address(&item.owner).unwrap_or_default();
                             }
                         }
                     }
@@ -1068,3 +1845,26 @@ impl SuiNetwork {
         return result_list;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SuiNetwork, BATCH_PAY_CHUNK_SIZE};
+
+    #[test]
+    fn batch_chunks_cover_every_payment_once() {
+        assert_eq!(SuiNetwork::batch_chunks(0), Vec::<(usize, usize)>::new());
+        assert_eq!(SuiNetwork::batch_chunks(1), vec![(0, 1)]);
+        assert_eq!(
+            SuiNetwork::batch_chunks(BATCH_PAY_CHUNK_SIZE),
+            vec![(0, BATCH_PAY_CHUNK_SIZE)]
+        );
+        assert_eq!(
+            SuiNetwork::batch_chunks(2 * BATCH_PAY_CHUNK_SIZE + 1),
+            vec![
+                (0, BATCH_PAY_CHUNK_SIZE),
+                (BATCH_PAY_CHUNK_SIZE, 2 * BATCH_PAY_CHUNK_SIZE),
+                (2 * BATCH_PAY_CHUNK_SIZE, 2 * BATCH_PAY_CHUNK_SIZE + 1),
+            ]
+        );
+    }
+}
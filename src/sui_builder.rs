@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use sui_sdk::{SUI_DEVNET_URL, SUI_LOCAL_NETWORK_URL, SUI_MAINNET_URL, SUI_TESTNET_URL};
+
+use crate::sui::SuiNetwork;
+use crate::sui_endpoint::EndpointPolicy;
+use crate::sui_error::{SuiChainError, SuiChainResult};
+use crate::sui_retry::RetryPolicy;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Localnet,
+}
+
+impl Network {
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => SUI_MAINNET_URL,
+            Network::Testnet => SUI_TESTNET_URL,
+            Network::Devnet => SUI_DEVNET_URL,
+            Network::Localnet => SUI_LOCAL_NETWORK_URL,
+        }
+    }
+
+    pub fn ws_url(&self) -> Option<&'static str> {
+        match self {
+            Network::Localnet => Some("ws://127.0.0.1:9000"),
+            _ => None,
+        }
+    }
+
+    /// Devnet and localnet are regenerated regularly, so their chain identifier is not fixed.
+    pub fn chain_identifier(&self) -> Option<&'static str> {
+        match self {
+            Network::Mainnet => Some("35834a8a"),
+            Network::Testnet => Some("4c78adac"),
+            Network::Devnet | Network::Localnet => None,
+        }
+    }
+}
+
+/// How long write operations wait after submitting a transaction.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WaitMode {
+    /// Return as soon as the effects are certified.
+    Effects,
+    /// Return once the node that served the request has executed the transaction,
+    /// so reads against it already see the result.
+    LocalExecution,
+    /// Poll until the transaction is included in a checkpoint, failing after `timeout`.
+    Checkpoint {
+        timeout: Duration,
+        poll_interval: Duration,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    pub network: Option<Network>,
+    pub rpc_url: String,
+    pub fallback_urls: Vec<String>,
+    pub endpoint_policy: EndpointPolicy,
+    pub ws_url: Option<String>,
+    pub request_timeout: Duration,
+    pub max_concurrent_requests: Option<usize>,
+    pub user_agent: Option<String>,
+    pub verify_chain_id: bool,
+    pub retry_policy: RetryPolicy,
+    /// Fraction added on top of the dry-run gas estimate, `0.2` means 20%.
+    pub gas_safety_margin: f64,
+    pub wait_mode: WaitMode,
+}
+
+impl NetworkConfig {
+    pub fn rpc_urls(&self) -> Vec<String> {
+        let mut rpc_urls = vec![self.rpc_url.clone()];
+        rpc_urls.extend(self.fallback_urls.iter().cloned());
+        rpc_urls
+    }
+
+    /// Rejects options the SUI client can not honour and invalid gas settings.
+    pub fn validate(&self) -> SuiChainResult<()> {
+        if self.user_agent.is_some() {
+            return Err(SuiChainError::Unsupported(
+                "custom User-Agent header, the SUI SDK client builder does not accept headers"
+                    .to_string(),
+            ));
+        }
+        if !self.gas_safety_margin.is_finite() || self.gas_safety_margin < 0.0 {
+            return Err(SuiChainError::InvalidConfig(format!(
+                "gas safety margin must be a finite value of at least 0, got {}",
+                self.gas_safety_margin
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn expected_chain_id(&self) -> Option<&'static str> {
+        if self.verify_chain_id {
+            self.network.and_then(|network| network.chain_identifier())
+        } else {
+            None
+        }
+    }
+}
+
+pub struct SuiNetworkBuilder {
+    network: Network,
+    rpc_url: Option<String>,
+    fallback_urls: Vec<String>,
+    endpoint_policy: EndpointPolicy,
+    ws_url: Option<String>,
+    request_timeout: Duration,
+    max_concurrent_requests: Option<usize>,
+    user_agent: Option<String>,
+    verify_chain_id: bool,
+    retry_policy: RetryPolicy,
+    gas_safety_margin: f64,
+    wait_mode: WaitMode,
+}
+
+impl Default for SuiNetworkBuilder {
+    fn default() -> Self {
+        SuiNetworkBuilder {
+            network: Network::Mainnet,
+            rpc_url: None,
+            fallback_urls: Vec::new(),
+            endpoint_policy: EndpointPolicy::PrimaryBackup,
+            ws_url: None,
+            request_timeout: Duration::from_secs(60),
+            max_concurrent_requests: None,
+            user_agent: None,
+            verify_chain_id: true,
+            retry_policy: RetryPolicy::default(),
+            gas_safety_margin: 0.2,
+            wait_mode: WaitMode::Effects,
+        }
+    }
+}
+
+impl SuiNetworkBuilder {
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Overrides the preset RPC URL. The chain identifier of the preset is still checked.
+    pub fn rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// Adds an endpoint used when the primary one fails or is rate limited.
+    pub fn fallback_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.fallback_urls.push(rpc_url.into());
+        self
+    }
+
+    pub fn endpoint_policy(mut self, endpoint_policy: EndpointPolicy) -> Self {
+        self.endpoint_policy = endpoint_policy;
+        self
+    }
+
+    pub fn ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = Some(ws_url.into());
+        self
+    }
+
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// `User-Agent` header for every request. The SUI SDK client builder this crate
+    /// is pinned to can not add custom headers, so `build` rejects it for now.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn verify_chain_id(mut self, verify_chain_id: bool) -> Self {
+        self.verify_chain_id = verify_chain_id;
+        self
+    }
+
+    /// Applied to every read call; write operations are never retried.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Extra gas budget on top of the dry-run estimate, as a fraction of the estimate.
+    pub fn gas_safety_margin(mut self, gas_safety_margin: f64) -> Self {
+        self.gas_safety_margin = gas_safety_margin;
+        self
+    }
+
+    pub fn wait_mode(mut self, wait_mode: WaitMode) -> Self {
+        self.wait_mode = wait_mode;
+        self
+    }
+
+    pub fn config(&self) -> NetworkConfig {
+        NetworkConfig {
+            network: Some(self.network),
+            rpc_url: self
+                .rpc_url
+                .clone()
+                .unwrap_or_else(|| self.network.rpc_url().to_string()),
+            fallback_urls: self.fallback_urls.clone(),
+            endpoint_policy: self.endpoint_policy,
+            ws_url: self
+                .ws_url
+                .clone()
+                .or_else(|| self.network.ws_url().map(|url| url.to_string())),
+            request_timeout: self.request_timeout,
+            max_concurrent_requests: self.max_concurrent_requests,
+            user_agent: self.user_agent.clone(),
+            verify_chain_id: self.verify_chain_id,
+            retry_policy: self.retry_policy.clone(),
+            gas_safety_margin: self.gas_safety_margin,
+            wait_mode: self.wait_mode,
+        }
+    }
+
+    pub fn build(self) -> SuiChainResult<SuiNetwork> {
+        let config = self.config();
+        config.validate()?;
+        Ok(SuiNetwork::with_config(config))
+    }
+}
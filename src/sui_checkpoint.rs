@@ -0,0 +1,176 @@
+use std::ops::RangeInclusive;
+
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use sui_json_rpc_types::{
+    Checkpoint, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::digests::TransactionDigest;
+use sui_types::sui_serde::BigInt;
+
+use crate::model::checkpoint::{CheckpointSummary, CheckpointWithTransactions};
+use crate::sui::SuiNetwork;
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+/// Most checkpoints a single `get_checkpoints` call returns.
+const MAX_CHECKPOINT_PAGE_SIZE: usize = 100;
+/// Most digests a single `multi_get_transactions_with_options` call accepts.
+const MAX_MULTI_GET_TRANSACTIONS: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct CheckpointStreamOptions {
+    /// Checkpoint pages, and transaction chunks, requested at the same time.
+    pub concurrency: usize,
+    /// Checkpoints per page, at most 100.
+    pub page_size: usize,
+    pub transaction_options: SuiTransactionBlockResponseOptions,
+}
+
+impl Default for CheckpointStreamOptions {
+    fn default() -> Self {
+        CheckpointStreamOptions {
+            concurrency: 4,
+            page_size: MAX_CHECKPOINT_PAGE_SIZE,
+            transaction_options: SuiTransactionBlockResponseOptions::full_content(),
+        }
+    }
+}
+
+impl SuiNetwork {
+    /// Yields the checkpoints of `range` in order, each with its transactions.
+    /// Up to `options.concurrency` pages are fetched ahead, and up to as many
+    /// checkpoints fetch their transactions at once, each in up to as many
+    /// chunks, so the node may see about `concurrency` squared requests at a time.
+    /// The stream ends early when it reaches the latest checkpoint, and yields an
+    /// error for a failed fetch or a missing checkpoint before ending.
+    pub fn checkpoint_stream(
+        &self,
+        range: RangeInclusive<u64>,
+        options: CheckpointStreamOptions,
+    ) -> impl Stream<Item = SuiChainResult<CheckpointWithTransactions>> + 'static {
+        let page_size = options.page_size.clamp(1, MAX_CHECKPOINT_PAGE_SIZE) as u64;
+        let concurrency = options.concurrency.max(1);
+        let (start, end) = range.into_inner();
+        let pages = (start..=end)
+            .step_by(page_size as usize)
+            .map(move |page_start| {
+                (
+                    page_start,
+                    page_size.min((end - page_start).saturating_add(1)) as usize,
+                )
+            });
+
+        let page_network = self.clone();
+        let transaction_network = self.clone();
+        stream::iter(pages)
+            .map(move |(page_start, limit)| {
+                let network = page_network.clone();
+                async move {
+                    let checkpoints = network.checkpoint_page(page_start, limit).await;
+                    checkpoints.map(|checkpoints| (checkpoints, limit))
+                }
+            })
+            .buffered(concurrency)
+            // Pages are fetched ahead, so a page past the tip may already hold
+            // checkpoints produced after a short page. Stop at the first short
+            // page and check the sequence numbers to never skip a checkpoint.
+            .scan((start, false), |(expected, done), page| {
+                if *done {
+                    return future::ready(None);
+                }
+                let checkpoints = match page {
+                    Ok((checkpoints, limit)) => {
+                        *done = checkpoints.len() < limit;
+                        let mut checked = Vec::with_capacity(checkpoints.len());
+                        for checkpoint in checkpoints {
+                            if checkpoint.sequence_number != *expected {
+                                *done = true;
+                                checked.push(Err(SuiChainError::NotFound(format!(
+                                    "checkpoint {}, node returned {}",
+                                    expected, checkpoint.sequence_number
+                                ))));
+                                break;
+                            }
+                            *expected += 1;
+                            checked.push(Ok(checkpoint));
+                        }
+                        checked
+                    }
+                    Err(error) => {
+                        *done = true;
+                        vec![Err(error)]
+                    }
+                };
+                future::ready(Some(stream::iter(checkpoints)))
+            })
+            .flatten()
+            .map(move |checkpoint| {
+                let network = transaction_network.clone();
+                let options = options.clone();
+                async move {
+                    let checkpoint = checkpoint?;
+                    let transactions = network
+                        .multi_get_transactions(&checkpoint.transactions, &options)
+                        .await?;
+                    Ok(CheckpointWithTransactions {
+                        checkpoint: CheckpointSummary::from(checkpoint),
+                        transactions,
+                    })
+                }
+            })
+            .buffered(concurrency)
+            .scan(false, |failed, checkpoint| {
+                if *failed {
+                    return future::ready(None);
+                }
+                *failed = checkpoint.is_err();
+                future::ready(Some(checkpoint))
+            })
+    }
+
+    async fn checkpoint_page(&self, start: u64, limit: usize) -> SuiChainResult<Vec<Checkpoint>> {
+        // The cursor is exclusive, so start right after the previous checkpoint.
+        let page = self
+            .read(|client| async move {
+                client
+                    .read_api()
+                    .get_checkpoints(start.checked_sub(1).map(BigInt::from), Some(limit), false)
+                    .await
+            })
+            .await?;
+        Ok(page.data)
+    }
+
+    /// Fetches transactions in chunks of 50, keeping their order.
+    pub(crate) async fn multi_get_transactions(
+        &self,
+        digests: &[TransactionDigest],
+        options: &CheckpointStreamOptions,
+    ) -> SuiChainResult<Vec<SuiTransactionBlockResponse>> {
+        let chunks: Vec<Vec<TransactionDigest>> = digests
+            .chunks(MAX_MULTI_GET_TRANSACTIONS)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        let responses: Vec<Vec<SuiTransactionBlockResponse>> = stream::iter(chunks)
+            .map(|chunk| {
+                let transaction_options = options.transaction_options.clone();
+                async move {
+                    self.read(|client| {
+                        let chunk = chunk.clone();
+                        let transaction_options = transaction_options.clone();
+                        async move {
+                            client
+                                .read_api()
+                                .multi_get_transactions_with_options(chunk, transaction_options)
+                                .await
+                        }
+                    })
+                    .await
+                }
+            })
+            .buffered(options.concurrency.max(1))
+            .try_collect()
+            .await?;
+        Ok(responses.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,258 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+/// Remembers where a checkpoint processor stopped. The stored value is the
+/// sequence number of the next checkpoint to process, the same value as
+/// `CheckpointFollower::cursor`.
+#[async_trait]
+pub trait CursorStore: Send + Sync {
+    /// The stored cursor, `None` when nothing was committed yet.
+    async fn load(&self) -> SuiChainResult<Option<u64>>;
+
+    async fn commit(&self, cursor: u64) -> SuiChainResult<()>;
+
+    /// Moves the cursor back so processing restarts at `sequence_number`.
+    async fn rewind(&self, sequence_number: u64) -> SuiChainResult<()> {
+        self.commit(sequence_number).await
+    }
+}
+
+/// Keeps the cursor as a decimal number in a text file. Commits write and sync a
+/// temporary file next to it, named after the cursor file with a `.tmp` suffix,
+/// then rename it over the cursor file, so a crash leaves either the old or the
+/// new value.
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        FileCursorStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[async_trait]
+impl CursorStore for FileCursorStore {
+    async fn load(&self) -> SuiChainResult<Option<u64>> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || read_cursor(&path))
+            .await
+            .map_err(|e| cursor_error(&self.path, e))?
+    }
+
+    async fn commit(&self, cursor: u64) -> SuiChainResult<()> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || write_cursor(&path, cursor))
+            .await
+            .map_err(|e| cursor_error(&self.path, e))?
+    }
+}
+
+fn read_cursor(path: &Path) -> SuiChainResult<Option<u64>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(cursor_error(path, e)),
+    };
+    let cursor = content
+        .trim()
+        .parse::<u64>()
+        .map_err(|e| cursor_error(path, e))?;
+    Ok(Some(cursor))
+}
+
+fn write_cursor(path: &Path, cursor: u64) -> SuiChainResult<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    let mut file = fs::File::create(&temp_path).map_err(|e| cursor_error(&temp_path, e))?;
+    file.write_all(cursor.to_string().as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| cursor_error(&temp_path, e))?;
+    fs::rename(&temp_path, path).map_err(|e| cursor_error(path, e))?;
+
+    // Sync the directory too, so the rename survives a power loss.
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| cursor_error(dir, e))?;
+    }
+    Ok(())
+}
+
+fn cursor_error(path: &Path, error: impl std::fmt::Display) -> SuiChainError {
+    SuiChainError::CursorStore(format!("{}: {}", path.display(), error))
+}
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteCursorStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use rusqlite::{params, Connection, OptionalExtension};
+
+    use super::CursorStore;
+    use crate::sui_error::{SuiChainError, SuiChainResult};
+
+    /// Keeps cursors in a SQLite table, one row per processor `name`, so several
+    /// indexers can share a database. Queries run on the blocking thread pool.
+    pub struct SqliteCursorStore {
+        connection: Arc<Mutex<Connection>>,
+        name: String,
+    }
+
+    impl SqliteCursorStore {
+        pub fn open(path: impl AsRef<Path>, name: impl Into<String>) -> SuiChainResult<Self> {
+            let connection = Connection::open(path).map_err(sqlite_error)?;
+            SqliteCursorStore::with_connection(connection, name)
+        }
+
+        /// Uses an existing connection, creating the cursor table if needed.
+        pub fn with_connection(
+            connection: Connection,
+            name: impl Into<String>,
+        ) -> SuiChainResult<Self> {
+            connection
+                .execute(
+                    "CREATE TABLE IF NOT EXISTS checkpoint_cursor (
+                        name TEXT PRIMARY KEY,
+                        next_sequence_number INTEGER NOT NULL
+                    )",
+                    [],
+                )
+                .map_err(sqlite_error)?;
+            Ok(SqliteCursorStore {
+                connection: Arc::new(Mutex::new(connection)),
+                name: name.into(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CursorStore for SqliteCursorStore {
+        async fn load(&self) -> SuiChainResult<Option<u64>> {
+            let connection = self.connection.clone();
+            let name = self.name.clone();
+            let cursor: Option<i64> = tokio::task::spawn_blocking(move || {
+                let connection = connection.lock().unwrap_or_else(|e| e.into_inner());
+                connection
+                    .query_row(
+                        "SELECT next_sequence_number FROM checkpoint_cursor WHERE name = ?1",
+                        params![name],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .map_err(sqlite_error)
+            })
+            .await
+            .map_err(|e| SuiChainError::CursorStore(e.to_string()))??;
+            Ok(cursor.map(|cursor| cursor as u64))
+        }
+
+        async fn commit(&self, cursor: u64) -> SuiChainResult<()> {
+            let connection = self.connection.clone();
+            let name = self.name.clone();
+            tokio::task::spawn_blocking(move || {
+                let connection = connection.lock().unwrap_or_else(|e| e.into_inner());
+                connection
+                    .execute(
+                        "INSERT INTO checkpoint_cursor (name, next_sequence_number) VALUES (?1, ?2)
+                        ON CONFLICT(name)
+                        DO UPDATE SET next_sequence_number = excluded.next_sequence_number",
+                        params![name, cursor as i64],
+                    )
+                    .map_err(sqlite_error)
+            })
+            .await
+            .map_err(|e| SuiChainError::CursorStore(e.to_string()))??;
+            Ok(())
+        }
+    }
+
+    fn sqlite_error(error: rusqlite::Error) -> SuiChainError {
+        SuiChainError::CursorStore(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{CursorStore, FileCursorStore};
+    use crate::sui_error::SuiChainError;
+
+    #[tokio::test]
+    async fn file_cursor_store_commits_and_rewinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let store = FileCursorStore::new(dir.join("checkpoint.cursor"));
+
+        assert_eq!(store.load().await.unwrap(), None);
+        store.commit(41).await.unwrap();
+        store.commit(42).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(42));
+        assert!(!dir.join("checkpoint.cursor.tmp").exists());
+        store.rewind(7).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(7));
+        assert_eq!(
+            FileCursorStore::new(dir.join("checkpoint.cursor"))
+                .load()
+                .await
+                .unwrap(),
+            Some(7)
+        );
+
+        fs::write(dir.join("checkpoint.cursor"), "").unwrap();
+        assert!(matches!(
+            store.load().await,
+            Err(SuiChainError::CursorStore(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn file_cursor_stores_with_the_same_stem_do_not_share_a_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let deposits = FileCursorStore::new(dir.path().join("indexer.deposits"));
+        let withdrawals = FileCursorStore::new(dir.path().join("indexer.withdrawals"));
+
+        let (first, second) = tokio::join!(deposits.commit(3), withdrawals.commit(8));
+        first.unwrap();
+        second.unwrap();
+        assert_eq!(deposits.load().await.unwrap(), Some(3));
+        assert_eq!(withdrawals.load().await.unwrap(), Some(8));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_cursor_store_keeps_one_cursor_per_name() {
+        use super::SqliteCursorStore;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cursor.db");
+        let deposits = SqliteCursorStore::open(&path, "deposits").unwrap();
+        let withdrawals = SqliteCursorStore::open(&path, "withdrawals").unwrap();
+
+        assert_eq!(deposits.load().await.unwrap(), None);
+        deposits.commit(100).await.unwrap();
+        withdrawals.commit(5).await.unwrap();
+        deposits.rewind(90).await.unwrap();
+        assert_eq!(deposits.load().await.unwrap(), Some(90));
+        assert_eq!(withdrawals.load().await.unwrap(), Some(5));
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use sui_sdk::{SuiClient, SuiClientBuilder};
+
+use crate::model::connection::ConnectionInfo;
+use crate::model::endpoint::EndpointMetrics;
+use crate::sui_builder::NetworkConfig;
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EndpointPolicy {
+    RoundRobin,
+    LowestLatency,
+    /// Always use the first healthy endpoint in configuration order.
+    PrimaryBackup,
+}
+
+struct Endpoint {
+    url: String,
+    client: RwLock<Option<SuiClient>>,
+    metrics: Mutex<EndpointMetrics>,
+}
+
+impl Endpoint {
+    fn client(&self) -> Option<SuiClient> {
+        self.client.read().unwrap().clone()
+    }
+
+    fn metrics(&self) -> EndpointMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    fn install(&self, sui_client: SuiClient, elapsed: Duration) {
+        *self.client.write().unwrap() = Some(sui_client);
+        self.metrics.lock().unwrap().connected = true;
+        self.record_success(elapsed);
+    }
+
+    fn record_success(&self, elapsed: Duration) {
+        let latency_ms = elapsed.as_millis() as u64;
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.requests += 1;
+        metrics.healthy = true;
+        metrics.last_latency_ms = Some(latency_ms);
+        metrics.average_latency_ms = Some(match metrics.average_latency_ms {
+            Some(average) => (average * 4 + latency_ms) / 5,
+            None => latency_ms,
+        });
+    }
+
+    fn record_failure(&self, error: &SuiChainError) {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.requests += 1;
+        metrics.failures += 1;
+        if error.is_rate_limited() {
+            metrics.rate_limited += 1;
+        }
+        if error.is_failover() {
+            metrics.healthy = false;
+        }
+        metrics.last_error = Some(error.to_string());
+    }
+}
+
+pub struct EndpointPool {
+    endpoints: Vec<Endpoint>,
+    policy: EndpointPolicy,
+    next: AtomicUsize,
+}
+
+impl EndpointPool {
+    pub fn new(urls: Vec<String>, policy: EndpointPolicy) -> Self {
+        EndpointPool {
+            endpoints: urls
+                .into_iter()
+                .map(|url| Endpoint {
+                    metrics: Mutex::new(EndpointMetrics::new(url.clone())),
+                    url,
+                    client: RwLock::new(None),
+                })
+                .collect(),
+            policy,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn metrics(&self) -> Vec<EndpointMetrics> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| endpoint.metrics())
+            .collect()
+    }
+
+    /// Connects every endpoint and returns the info of the first one that came up.
+    /// A chain identifier mismatch on any endpoint fails the whole pool, and no
+    /// client is installed then.
+    pub async fn connect(&self, config: &NetworkConfig) -> SuiChainResult<ConnectionInfo> {
+        let mut clients = Vec::with_capacity(self.endpoints.len());
+        for endpoint in &self.endpoints {
+            let started = Instant::now();
+            let result = build_client(&endpoint.url, config).await;
+            if let Err(error @ SuiChainError::ChainIdMismatch { .. }) = result {
+                endpoint.record_failure(&error);
+                return Err(error);
+            }
+            clients.push((result, started.elapsed()));
+        }
+
+        let mut connection_info = None;
+        let mut first_error = None;
+        for (endpoint, (result, elapsed)) in self.endpoints.iter().zip(clients) {
+            match result {
+                Ok((sui_client, info)) => {
+                    endpoint.install(sui_client, elapsed);
+                    connection_info.get_or_insert(info);
+                }
+                Err(error) => {
+                    endpoint.record_failure(&error);
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        match connection_info {
+            Some(info) => Ok(info),
+            None => Err(first_error.unwrap_or(SuiChainError::NotConnected)),
+        }
+    }
+
+    async fn connect_endpoint(
+        &self,
+        index: usize,
+        config: &NetworkConfig,
+    ) -> SuiChainResult<ConnectionInfo> {
+        let endpoint = &self.endpoints[index];
+        let started = Instant::now();
+        match build_client(&endpoint.url, config).await {
+            Ok((sui_client, info)) => {
+                endpoint.install(sui_client, started.elapsed());
+                Ok(info)
+            }
+            Err(error) => {
+                endpoint.record_failure(&error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Reconnects endpoints that never came up and pings the others,
+    /// so unhealthy endpoints rejoin the rotation once they recover.
+    pub async fn probe(&self, config: &NetworkConfig) {
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            match endpoint.client() {
+                Some(sui_client) => {
+                    let started = Instant::now();
+                    match sui_client
+                        .read_api()
+                        .get_latest_checkpoint_sequence_number()
+                        .await
+                    {
+                        Ok(_) => endpoint.record_success(started.elapsed()),
+                        Err(e) => endpoint.record_failure(&SuiChainError::from(e)),
+                    }
+                }
+                None => {
+                    if let Err(error) = self.connect_endpoint(index, config).await {
+                        tracing::debug!(url = %endpoint.url, %error, "endpoint still down");
+                    }
+                }
+            }
+        }
+    }
+
+    /// A pool of only the endpoint `call` would use first, sharing its client, so
+    /// dependent calls all see what the earlier ones executed. It does not fail
+    /// over, and its metrics are kept apart from this pool.
+    pub(crate) fn pinned(&self) -> SuiChainResult<EndpointPool> {
+        let endpoint = self
+            .candidates()
+            .into_iter()
+            .map(|index| &self.endpoints[index])
+            .find(|endpoint| endpoint.client().is_some())
+            .ok_or(SuiChainError::NotConnected)?;
+        Ok(EndpointPool {
+            endpoints: vec![Endpoint {
+                url: endpoint.url.clone(),
+                client: RwLock::new(endpoint.client()),
+                metrics: Mutex::new(endpoint.metrics()),
+            }],
+            policy: EndpointPolicy::PrimaryBackup,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Healthy endpoints in policy order, followed by the unhealthy ones as a last resort.
+    fn candidates(&self) -> Vec<usize> {
+        let snapshot: Vec<EndpointMetrics> = self.metrics();
+        let (mut healthy, unhealthy): (Vec<usize>, Vec<usize>) =
+            (0..self.endpoints.len()).partition(|index| snapshot[*index].healthy);
+        match self.policy {
+            EndpointPolicy::RoundRobin => {
+                if !healthy.is_empty() {
+                    let start = self.next.fetch_add(1, Ordering::Relaxed) % healthy.len();
+                    healthy.rotate_left(start);
+                }
+            }
+            EndpointPolicy::LowestLatency => {
+                // Endpoints that were never measured go after the measured ones.
+                healthy
+                    .sort_by_key(|index| snapshot[*index].average_latency_ms.unwrap_or(u64::MAX));
+            }
+            EndpointPolicy::PrimaryBackup => {}
+        }
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    /// Runs `request` against the selected endpoint, moving on to the next one
+    /// when the endpoint fails with a transport error or rate limiting.
+    pub async fn call<T, F, Fut>(&self, request: F) -> SuiChainResult<T>
+    where
+        F: Fn(SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, sui_sdk::error::Error>>,
+    {
+        let mut last_error = SuiChainError::NotConnected;
+        for index in self.candidates() {
+            let endpoint = &self.endpoints[index];
+            let Some(sui_client) = endpoint.client() else {
+                continue;
+            };
+            let started = Instant::now();
+            match request(sui_client).await {
+                Ok(value) => {
+                    endpoint.record_success(started.elapsed());
+                    return Ok(value);
+                }
+                Err(e) => {
+                    let error = SuiChainError::from(e);
+                    endpoint.record_failure(&error);
+                    if !error.is_failover() {
+                        return Err(error);
+                    }
+                    tracing::warn!(url = %endpoint.url, %error, "endpoint failed, failing over");
+                    last_error = error;
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+async fn build_client(
+    url: &str,
+    config: &NetworkConfig,
+) -> SuiChainResult<(SuiClient, ConnectionInfo)> {
+    config.validate()?;
+    let mut client_builder = SuiClientBuilder::default().request_timeout(config.request_timeout);
+    if let Some(max_concurrent_requests) = config.max_concurrent_requests {
+        client_builder = client_builder.max_concurrent_requests(max_concurrent_requests);
+    }
+    if let Some(ws_url) = &config.ws_url {
+        client_builder = client_builder.ws_url(ws_url);
+    }
+    let sui_client = client_builder
+        .build(url)
+        .await
+        .map_err(|source| SuiChainError::Connect {
+            url: url.to_string(),
+            source,
+        })?;
+
+    let read_api = sui_client.read_api();
+    let chain_identifier = read_api.get_chain_identifier().await?;
+    if let Some(expected) = config.expected_chain_id() {
+        if chain_identifier != expected {
+            return Err(SuiChainError::ChainIdMismatch {
+                expected: expected.to_string(),
+                actual: chain_identifier,
+            });
+        }
+    }
+    let latest_checkpoint = read_api.get_latest_checkpoint_sequence_number().await?;
+    let reference_gas_price = read_api.get_reference_gas_price().await?;
+
+    let connection_info = ConnectionInfo {
+        rpc_url: url.to_string(),
+        rpc_api_version: sui_client.api_version().to_string(),
+        chain_identifier,
+        latest_checkpoint,
+        reference_gas_price,
+    };
+    Ok((sui_client, connection_info))
+}
@@ -0,0 +1,115 @@
+use jsonrpsee::http_client::transport::Error as HttpTransportError;
+use sui_types::base_types::SuiAddress;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SuiChainError {
+    #[error("not connected to SUI node")]
+    NotConnected,
+    #[error("could not connect to {url}: {source}")]
+    Connect {
+        url: String,
+        #[source]
+        source: sui_sdk::error::Error,
+    },
+    #[error("invalid network configuration: {0}")]
+    InvalidConfig(String),
+    #[error("not supported: {0}")]
+    Unsupported(String),
+    #[error("chain identifier mismatch: expected {expected}, node reported {actual}")]
+    ChainIdMismatch { expected: String, actual: String },
+    #[error("no private key assigned")]
+    NoSigner,
+    #[error("invalid private key: {0}")]
+    InvalidPrivateKey(String),
+    #[error("unsupported key scheme: {0}")]
+    UnsupportedKeyScheme(String),
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("keystore error: {0}")]
+    Keystore(String),
+    #[error("cursor store error: {0}")]
+    CursorStore(String),
+    #[error("invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("invalid transaction digest: {0}")]
+    InvalidDigest(String),
+    #[error("invalid transaction data: {0}")]
+    InvalidTransaction(String),
+    #[error("rpc error: {source}")]
+    Rpc {
+        #[from]
+        source: sui_sdk::error::Error,
+    },
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("insufficient gas for {address}")]
+    InsufficientGas { address: SuiAddress },
+    #[error("insufficient {coin_type} balance for {address}, {amount} required")]
+    InsufficientBalance {
+        address: SuiAddress,
+        coin_type: String,
+        amount: u128,
+    },
+    #[error("could not build transaction: {0}")]
+    TransactionBuild(String),
+    #[error("signature error: {0}")]
+    Signature(String),
+    /// `digest` is set when the transaction was executed and charged for gas,
+    /// and empty when it failed in a dry run.
+    #[error("transaction execution failed: {status}")]
+    ExecutionFailed {
+        digest: Option<String>,
+        status: String,
+    },
+    #[error("transaction {digest} was not checkpointed in time")]
+    CheckpointTimeout { digest: String },
+}
+
+pub type SuiChainResult<T> = Result<T, SuiChainError>;
+
+impl SuiChainError {
+    fn rpc_error(&self) -> Option<&jsonrpsee::core::Error> {
+        match self {
+            SuiChainError::Rpc {
+                source: sui_sdk::error::Error::RpcError(rpc_error),
+            } => Some(rpc_error),
+            _ => None,
+        }
+    }
+
+    /// The HTTP client reports a rejected request as a transport error carrying
+    /// the status code.
+    pub fn is_rate_limited(&self) -> bool {
+        match self.rpc_error() {
+            Some(jsonrpsee::core::Error::Transport(error)) => matches!(
+                error.downcast_ref::<HttpTransportError>(),
+                Some(HttpTransportError::RequestFailure { status_code: 429 })
+            ),
+            _ => false,
+        }
+    }
+
+    pub fn is_transport(&self) -> bool {
+        match self {
+            SuiChainError::NotConnected | SuiChainError::Connect { .. } => true,
+            _ => matches!(
+                self.rpc_error(),
+                Some(
+                    jsonrpsee::core::Error::Transport(_)
+                        | jsonrpsee::core::Error::RequestTimeout
+                        | jsonrpsee::core::Error::RestartNeeded(_)
+                )
+            ),
+        }
+    }
+
+    /// Errors caused by the endpoint rather than the request, worth trying on another node.
+    pub fn is_failover(&self) -> bool {
+        self.is_transport() || self.is_rate_limited()
+    }
+
+    /// Default classification used by `RetryPolicy`.
+    pub fn is_retryable(&self) -> bool {
+        self.is_failover()
+    }
+}
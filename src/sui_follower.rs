@@ -0,0 +1,159 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures::stream::{self, Stream};
+
+use crate::model::checkpoint::CheckpointSummary;
+use crate::sui::SuiNetwork;
+use crate::sui_cursor::CursorStore;
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+/// Attempt passed to `RetryPolicy::backoff` during an outage, well past the
+/// point where the delay reaches `max_backoff`.
+pub(crate) const MAX_BACKOFF_ATTEMPT: u32 = 32;
+
+/// Tails the chain one checkpoint at a time, in order and without gaps.
+///
+/// The cursor is the sequence number of the next checkpoint to yield. Persist
+/// `cursor()` after handling a checkpoint and pass it to `new` after a restart
+/// to continue where the follower stopped.
+pub struct CheckpointFollower {
+    network: SuiNetwork,
+    cursor: u64,
+    latest: Option<u64>,
+    poll_interval: Duration,
+    failures: u32,
+}
+
+impl CheckpointFollower {
+    pub fn new(network: SuiNetwork, cursor: u64) -> Self {
+        CheckpointFollower {
+            network,
+            cursor,
+            latest: None,
+            poll_interval: Duration::from_secs(1),
+            failures: 0,
+        }
+    }
+
+    /// Starts at the latest checkpoint of the network.
+    pub async fn from_tip(network: SuiNetwork) -> SuiChainResult<Self> {
+        let latest = network.get_latest_checkpoint_no().await?;
+        let mut follower = CheckpointFollower::new(network, latest);
+        follower.latest = Some(latest);
+        Ok(follower)
+    }
+
+    /// Continues from the cursor committed to `store`, or starts at the tip when
+    /// nothing was committed yet.
+    pub async fn resume(network: SuiNetwork, store: &dyn CursorStore) -> SuiChainResult<Self> {
+        match store.load().await? {
+            Some(cursor) => Ok(CheckpointFollower::new(network, cursor)),
+            None => CheckpointFollower::from_tip(network).await,
+        }
+    }
+
+    /// How long to wait before asking again when the follower is at the tip.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    pub(crate) fn network(&self) -> &SuiNetwork {
+        &self.network
+    }
+
+    /// Waits for the checkpoint at the cursor and moves the cursor past it.
+    /// Retryable errors, see `SuiChainError::is_retryable`, are logged and
+    /// retried with the backoff of the network retry policy. Other errors are
+    /// returned and leave the cursor where it was.
+    pub async fn next(&mut self) -> SuiChainResult<CheckpointSummary> {
+        loop {
+            if self.latest.map_or(true, |latest| self.cursor > latest) {
+                match self.network.get_latest_checkpoint_no().await {
+                    Ok(latest) => self.latest = Some(latest),
+                    Err(error) => {
+                        self.wait_after_error(error).await?;
+                        continue;
+                    }
+                }
+                if self.latest.map_or(true, |latest| self.cursor > latest) {
+                    tokio::time::sleep(self.poll_interval).await;
+                    continue;
+                }
+            }
+
+            match self.network.get_checkpoint(self.cursor).await {
+                Ok(checkpoint) => {
+                    self.failures = 0;
+                    self.cursor += 1;
+                    return Ok(checkpoint);
+                }
+                Err(error) => self.wait_after_error(error).await?,
+            }
+        }
+    }
+
+    /// Hands every checkpoint to `handler` and commits the cursor to `store` once
+    /// the handler succeeded. Delivery is at least once: a checkpoint handled
+    /// right before a crash is handed over again after a restart. Returns on the
+    /// first follower, handler or store error; the cursor only moves past a
+    /// checkpoint once its commit succeeded, so a failed checkpoint is retried by
+    /// the next call.
+    pub async fn process<F, Fut>(
+        &mut self,
+        store: &dyn CursorStore,
+        mut handler: F,
+    ) -> SuiChainResult<()>
+    where
+        F: FnMut(CheckpointSummary) -> Fut,
+        Fut: Future<Output = SuiChainResult<()>>,
+    {
+        loop {
+            let checkpoint = self.next().await?;
+            let sequence_number = checkpoint.sequence_number;
+            let result = match handler(checkpoint).await {
+                Ok(()) => store.commit(self.cursor).await,
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                self.cursor = sequence_number;
+                return Err(error);
+            }
+        }
+    }
+
+    /// Sleeps for the backoff of a retryable `error`, or returns it.
+    async fn wait_after_error(&mut self, error: SuiChainError) -> SuiChainResult<()> {
+        if !error.is_retryable() {
+            return Err(error);
+        }
+        self.failures = (self.failures + 1).min(MAX_BACKOFF_ATTEMPT);
+        let backoff = self.network.config().retry_policy.backoff(self.failures);
+        tracing::warn!(
+            cursor = self.cursor,
+            failures = self.failures,
+            ?backoff,
+            %error,
+            "checkpoint follower failed, retrying"
+        );
+        tokio::time::sleep(backoff).await;
+        Ok(())
+    }
+
+    /// Turns the follower into a stream of checkpoints that ends after the first
+    /// error.
+    pub fn into_stream(self) -> impl Stream<Item = SuiChainResult<CheckpointSummary>> {
+        stream::unfold(Some(self), |follower| async move {
+            let mut follower = follower?;
+            match follower.next().await {
+                Ok(checkpoint) => Some((Ok(checkpoint), Some(follower))),
+                Err(error) => Some((Err(error), None)),
+            }
+        })
+    }
+}
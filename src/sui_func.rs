@@ -1,86 +1,269 @@
+use std::str::FromStr;
+
 use base64::{prelude::BASE64_STANDARD, Engine};
+use bip32::DerivationPath;
+use bip39::{Language, Mnemonic, Seed};
+use sui_json_rpc_types::{Balance, ObjectsPage};
+use sui_keys::key_derive::{derive_key_pair_from_path, generate_new_key};
 use sui_sdk::{
     types::{
         base_types::SuiAddress,
-        crypto::{get_key_pair_from_rng, SuiKeyPair},
+        crypto::{
+            get_key_pair_from_rng, EncodeDecodeBase64, SignatureScheme, SuiKeyPair,
+            SUI_PRIV_KEY_PREFIX,
+        },
     },
     SuiClientBuilder,
 };
 
-pub async fn get_wallet_all_balance(node_addr: String, wallet_address: SuiAddress) {
-    match SuiClientBuilder::default().build(node_addr.clone()).await {
-        Ok(sui) => {
-            let coin_read = sui.coin_read_api();
-            match coin_read.get_all_balances(wallet_address).await {
-                Ok(total_balance) => {
-                    println!("wallet balance: {:?}", total_balance);
-                }
-                Err(_) => {
-                    println!("balance error");
-                }
-            };
-        }
-        Err(_) => {
-            println!("client connection error");
-        }
-    }
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+pub async fn get_wallet_all_balance(
+    node_addr: String,
+    wallet_address: SuiAddress,
+) -> SuiChainResult<Vec<Balance>> {
+    let sui = SuiClientBuilder::default().build(node_addr.clone()).await?;
+    let total_balance = sui.coin_read_api().get_all_balances(wallet_address).await?;
+    Ok(total_balance)
 }
 
-pub async fn get_wallet_balance(node_addr: String, wallet_address: SuiAddress, coin_type: String) {
-    match SuiClientBuilder::default().build(node_addr.clone()).await {
-        Ok(sui) => {
-            match sui
-                .coin_read_api()
-                .get_balance(wallet_address, Some(coin_type))
-                .await
-            {
-                Ok(total_balance) => {
-                    println!("wallet balance: {:?}", total_balance);
-                }
-                Err(_) => {
-                    println!("balance error");
-                }
-            };
-        }
-        Err(_) => {
-            println!("client connection error");
-        }
+pub async fn get_wallet_balance(
+    node_addr: String,
+    wallet_address: SuiAddress,
+    coin_type: String,
+) -> SuiChainResult<Balance> {
+    let sui = SuiClientBuilder::default().build(node_addr.clone()).await?;
+    let total_balance = sui
+        .coin_read_api()
+        .get_balance(wallet_address, Some(coin_type))
+        .await?;
+    Ok(total_balance)
+}
+
+pub async fn get_wallet_owned_object(
+    node_addr: String,
+    wallet_address: SuiAddress,
+) -> SuiChainResult<ObjectsPage> {
+    let sui = SuiClientBuilder::default().build(node_addr.clone()).await?;
+    let owned_objects = sui
+        .read_api()
+        .get_owned_objects(wallet_address.clone(), None, None, Some(5))
+        .await?;
+    Ok(owned_objects)
+}
+
+/// Generates a key pair for `scheme`, one of `ED25519`, `Secp256k1` or `Secp256r1`.
+pub fn generate_keypair(scheme: SignatureScheme) -> SuiChainResult<SuiKeyPair> {
+    let mut rng = rand::rngs::OsRng;
+    match scheme {
+        SignatureScheme::ED25519 => Ok(SuiKeyPair::Ed25519(get_key_pair_from_rng(&mut rng).1)),
+        SignatureScheme::Secp256k1 => Ok(SuiKeyPair::Secp256k1(get_key_pair_from_rng(&mut rng).1)),
+        SignatureScheme::Secp256r1 => Ok(SuiKeyPair::Secp256r1(get_key_pair_from_rng(&mut rng).1)),
+        other => Err(SuiChainError::UnsupportedKeyScheme(other.to_string())),
     }
 }
 
-pub async fn get_wallet_owned_object(node_addr: String, wallet_address: SuiAddress) {
-    match SuiClientBuilder::default().build(node_addr.clone()).await {
-        Ok(sui) => {
-            match sui
-                .read_api()
-                .get_owned_objects(wallet_address.clone(), None, None, Some(5))
-                .await
-            {
-                Ok(owned_objects) => {
-                    println!("// ************ READ API ************ //\n");
-                    println!("owned_objects {:?}", owned_objects);
-                }
-                Err(_) => {
-                    println!("object read error");
-                }
-            }
-        }
-        Err(_) => {
-            println!("client connection error");
-        }
+pub fn private_key_string_to_keypair(keypair_bytes: Vec<u8>) -> SuiChainResult<SuiKeyPair> {
+    SuiKeyPair::from_bytes(&keypair_bytes)
+        .map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))
+}
+
+pub fn base64_private_key_to_wallet_address(
+    base64_private_key: String,
+) -> SuiChainResult<SuiAddress> {
+    let private_key_vec = BASE64_STANDARD
+        .decode(base64_private_key)
+        .map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))?;
+    let key_pair = private_key_string_to_keypair(private_key_vec)?;
+    Ok(SuiAddress::from(&key_pair.public()))
+}
+
+/// Accepts a Bech32 `suiprivkey1...` string or base64 flag-prefixed key bytes.
+pub fn parse_private_key(private_key: &str) -> SuiChainResult<SuiKeyPair> {
+    let private_key = private_key.trim();
+    if private_key.starts_with(SUI_PRIV_KEY_PREFIX) {
+        bech32_private_key_to_keypair(private_key)
+    } else {
+        let private_key_vec = BASE64_STANDARD
+            .decode(private_key)
+            .map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))?;
+        private_key_string_to_keypair(private_key_vec)
     }
 }
 
-pub fn generate_keypair() -> SuiKeyPair {
-    SuiKeyPair::Ed25519(get_key_pair_from_rng(&mut rand::rngs::OsRng).1)
+pub fn private_key_to_wallet_address(private_key: String) -> SuiChainResult<SuiAddress> {
+    let key_pair = parse_private_key(&private_key)?;
+    Ok(SuiAddress::from(&key_pair.public()))
+}
+
+pub fn bech32_private_key_to_keypair(private_key: &str) -> SuiChainResult<SuiKeyPair> {
+    SuiKeyPair::decode(private_key).map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))
+}
+
+/// Exports the key in the `suiprivkey1...` format used by Sui wallets.
+pub fn keypair_to_bech32(key_pair: &SuiKeyPair) -> SuiChainResult<String> {
+    key_pair
+        .encode()
+        .map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))
+}
+
+pub fn keypair_to_base64(key_pair: &SuiKeyPair) -> String {
+    key_pair.encode_base64()
 }
 
-pub fn private_key_string_to_keypair(keypair_bytes: Vec<u8>) -> SuiKeyPair {
-    SuiKeyPair::from_bytes(&keypair_bytes).unwrap()
+/// Derives the key of a BIP-39 mnemonic. Without `derivation_path` the default
+/// Sui path of the scheme is used, `m/44'/784'/0'/0'/0'` for Ed25519,
+/// `m/54'/784'/0'/0/0` for Secp256k1 and `m/74'/784'/0'/0/0` for Secp256r1.
+pub fn mnemonic_to_keypair(
+    phrase: &str,
+    scheme: SignatureScheme,
+    derivation_path: Option<&str>,
+) -> SuiChainResult<SuiKeyPair> {
+    let mnemonic = Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|e| SuiChainError::InvalidMnemonic(e.to_string()))?;
+    let derivation_path = match derivation_path {
+        Some(path) => Some(
+            DerivationPath::from_str(path)
+                .map_err(|e| SuiChainError::InvalidMnemonic(e.to_string()))?,
+        ),
+        None => None,
+    };
+    let seed = Seed::new(&mnemonic, "");
+    let (_, key_pair) = derive_key_pair_from_path(seed.as_bytes(), derivation_path, &scheme)
+        .map_err(|e| SuiChainError::InvalidMnemonic(e.to_string()))?;
+    Ok(key_pair)
 }
 
-pub fn base64_private_key_to_wallet_address(base64_private_key: String) -> SuiAddress {
-    let private_key_vec = BASE64_STANDARD.decode(base64_private_key).unwrap();
-    let key_pair = private_key_string_to_keypair(private_key_vec);
-    SuiAddress::from(&key_pair.public())
+/// Generates a new wallet backed by a mnemonic of `word_count` words (12, 15, 18,
+/// 21 or 24, 12 by default), derived on the default path of `scheme`.
+/// Returns the phrase together with the key pair.
+pub fn generate_mnemonic_wallet(
+    scheme: SignatureScheme,
+    word_count: Option<usize>,
+) -> SuiChainResult<(String, SuiKeyPair)> {
+    let word_length = word_count.map(|count| format!("word{}", count));
+    let (_, key_pair, _, phrase) = generate_new_key(scheme, None, word_length)
+        .map_err(|e| SuiChainError::InvalidMnemonic(e.to_string()))?;
+    Ok((phrase, key_pair))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::SuiAddress;
+    use sui_types::crypto::{EncodeDecodeBase64, SignatureScheme, SUI_PRIV_KEY_PREFIX};
+
+    use super::{
+        base64_private_key_to_wallet_address, generate_keypair, keypair_to_base64,
+        keypair_to_bech32, mnemonic_to_keypair, parse_private_key,
+    };
+    use crate::sui_error::SuiChainError;
+
+    const FILM_CRAZY: &str = "film crazy soon outside stand loop subway crumble thrive popular \
+        green nuclear struggle pistol arm wife phrase warfare march wheat nephew ask sunny firm";
+    const ACT_WING: &str =
+        "act wing dilemma glory episode region allow mad tourist humble muffin shoe";
+
+    const SCHEMES: [SignatureScheme; 3] = [
+        SignatureScheme::ED25519,
+        SignatureScheme::Secp256k1,
+        SignatureScheme::Secp256r1,
+    ];
+
+    /// Vectors of the Sui SDK key derivation tests, on the default path.
+    #[test]
+    fn mnemonic_derives_the_known_address_of_each_scheme() {
+        for (phrase, scheme, address) in [
+            (
+                FILM_CRAZY,
+                SignatureScheme::ED25519,
+                "0xa2d14fad60c56049ecf75246a481934691214ce413e6a8ae2fe6834c173a6133",
+            ),
+            (
+                FILM_CRAZY,
+                SignatureScheme::Secp256k1,
+                "0x9e8f732575cc5386f8df3c784cd3ed1b53ce538da79926b2ad54dcc1197d2532",
+            ),
+            (
+                ACT_WING,
+                SignatureScheme::Secp256r1,
+                "0x4a822457f1970468d38dae8e63fb60eedf570ceb2ca09b74bd1bd60ed3de8db3",
+            ),
+        ] {
+            let key_pair = mnemonic_to_keypair(phrase, scheme.clone(), None).unwrap();
+            assert_eq!(
+                SuiAddress::from(&key_pair.public()),
+                address.parse::<SuiAddress>().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn mnemonic_with_the_default_path_matches_no_path() {
+        let with_path = mnemonic_to_keypair(
+            FILM_CRAZY,
+            SignatureScheme::ED25519,
+            Some("m/44'/784'/0'/0'/0'"),
+        )
+        .unwrap();
+        let without_path = mnemonic_to_keypair(FILM_CRAZY, SignatureScheme::ED25519, None).unwrap();
+        assert_eq!(with_path.public(), without_path.public());
+
+        let other_account = mnemonic_to_keypair(
+            FILM_CRAZY,
+            SignatureScheme::ED25519,
+            Some("m/44'/784'/1'/0'/0'"),
+        )
+        .unwrap();
+        assert_ne!(other_account.public(), without_path.public());
+    }
+
+    #[test]
+    fn invalid_mnemonic_is_rejected() {
+        assert!(matches!(
+            mnemonic_to_keypair("film crazy soon", SignatureScheme::ED25519, None),
+            Err(SuiChainError::InvalidMnemonic(_))
+        ));
+    }
+
+    #[test]
+    fn bech32_round_trip_for_each_scheme() {
+        for scheme in SCHEMES {
+            let key_pair = generate_keypair(scheme).unwrap();
+            let bech32 = keypair_to_bech32(&key_pair).unwrap();
+            assert!(bech32.starts_with(SUI_PRIV_KEY_PREFIX));
+
+            let parsed = parse_private_key(&bech32).unwrap();
+            assert_eq!(parsed.public(), key_pair.public());
+            assert_eq!(keypair_to_bech32(&parsed).unwrap(), bech32);
+        }
+    }
+
+    #[test]
+    fn base64_form_is_flag_prefixed_and_round_trips() {
+        for scheme in SCHEMES {
+            let key_pair = generate_keypair(scheme.clone()).unwrap();
+            let base64 = keypair_to_base64(&key_pair);
+            assert_eq!(base64, key_pair.encode_base64());
+
+            let parsed = parse_private_key(&format!(" {} ", base64)).unwrap();
+            assert_eq!(parsed.public(), key_pair.public());
+            assert_eq!(
+                base64_private_key_to_wallet_address(base64).unwrap(),
+                SuiAddress::from(&key_pair.public())
+            );
+            assert_eq!(parsed.public().flag(), scheme.flag());
+        }
+    }
+
+    #[test]
+    fn garbage_private_key_is_rejected() {
+        assert!(matches!(
+            parse_private_key("suiprivkey1notakey"),
+            Err(SuiChainError::InvalidPrivateKey(_))
+        ));
+        assert!(matches!(
+            parse_private_key("not base64!"),
+            Err(SuiChainError::InvalidPrivateKey(_))
+        ));
+    }
 }
@@ -0,0 +1,410 @@
+use std::{fs, path::Path};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, SuiKeyPair};
+use zeroize::Zeroizing;
+
+use crate::sui_error::{SuiChainError, SuiChainResult};
+use crate::sui_func::{keypair_to_bech32, parse_private_key};
+
+const KEYSTORE_VERSION: u32 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Named signing keys, kept in a password encrypted file or loaded from the
+/// `sui.keystore` file written by the Sui CLI.
+#[derive(Default)]
+pub struct SuiKeystore {
+    keys: Vec<KeystoreEntry>,
+}
+
+struct KeystoreEntry {
+    alias: String,
+    address: SuiAddress,
+    key_pair: SuiKeyPair,
+}
+
+/// Plain content of an encrypted keystore.
+#[derive(Serialize, Deserialize)]
+struct StoredKey {
+    alias: String,
+    /// Bech32 `suiprivkey1...` string.
+    private_key: String,
+}
+
+/// On-disk layout of an encrypted keystore. The key is derived from the
+/// password with Argon2id and the stored keys are sealed with AES-256-GCM.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u32,
+    kdf: String,
+    cipher: String,
+    /// Absent in files written before the parameters were stored, which used
+    /// the Argon2 defaults.
+    #[serde(default)]
+    kdf_params: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Argon2id cost parameters the key was derived with.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Entry of the `sui.aliases` file next to `sui.keystore`.
+#[derive(Deserialize)]
+struct SuiAlias {
+    alias: String,
+    public_key_base64: String,
+}
+
+impl SuiKeystore {
+    pub fn new() -> Self {
+        SuiKeystore::default()
+    }
+
+    /// Loads a `sui.keystore` file, a JSON array of base64 flag-prefixed keys.
+    /// Aliases are read from the `sui.aliases` file in the same directory when it
+    /// exists, otherwise every key is aliased by its address.
+    pub fn load_sui_keystore(path: impl AsRef<Path>) -> SuiChainResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| keystore_error(path, e))?;
+        let private_keys: Vec<String> =
+            serde_json::from_str(&content).map_err(|e| keystore_error(path, e))?;
+
+        let aliases_path = path.with_file_name("sui.aliases");
+        let aliases: Vec<SuiAlias> = match fs::read_to_string(&aliases_path) {
+            Ok(content) => {
+                serde_json::from_str(&content).map_err(|e| keystore_error(&aliases_path, e))?
+            }
+            Err(_) => Vec::new(),
+        };
+
+        let mut keystore = SuiKeystore::new();
+        for private_key in private_keys {
+            let key_pair = SuiKeyPair::decode_base64(&private_key)
+                .map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))?;
+            let public_key = key_pair.public().encode_base64();
+            let alias = match aliases
+                .iter()
+                .find(|alias| alias.public_key_base64 == public_key)
+            {
+                Some(alias) => alias.alias.clone(),
+                None => SuiAddress::from(&key_pair.public()).to_string(),
+            };
+            keystore.add(alias, key_pair)?;
+        }
+        Ok(keystore)
+    }
+
+    /// Opens a keystore written by `save_encrypted`.
+    pub fn open_encrypted(path: impl AsRef<Path>, password: &str) -> SuiChainResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| keystore_error(path, e))?;
+        let file: EncryptedKeystoreFile =
+            serde_json::from_str(&content).map_err(|e| keystore_error(path, e))?;
+        if file.version != KEYSTORE_VERSION {
+            return Err(SuiChainError::Keystore(format!(
+                "{}: unsupported keystore version {}",
+                path.display(),
+                file.version
+            )));
+        }
+        let salt = decode_field(path, &file.salt)?;
+        let nonce = decode_field(path, &file.nonce)?;
+        let ciphertext = decode_field(path, &file.ciphertext)?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(keystore_error(path, "invalid nonce length"));
+        }
+
+        let cipher = derive_cipher(password, &salt, file.kdf_params)?;
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map(Zeroizing::new)
+            .map_err(|_| keystore_error(path, "wrong password or corrupted file"))?;
+        let stored_keys: Vec<StoredKey> =
+            serde_json::from_slice(&plaintext).map_err(|e| keystore_error(path, e))?;
+
+        let mut keystore = SuiKeystore::new();
+        for stored_key in stored_keys {
+            keystore.add(
+                stored_key.alias,
+                parse_private_key(&stored_key.private_key)?,
+            )?;
+        }
+        Ok(keystore)
+    }
+
+    /// Writes every key to `path`, encrypted with `password`. A new salt and
+    /// nonce are generated on each save.
+    pub fn save_encrypted(&self, path: impl AsRef<Path>, password: &str) -> SuiChainResult<()> {
+        let path = path.as_ref();
+        let stored_keys = self
+            .keys
+            .iter()
+            .map(|entry| {
+                Ok(StoredKey {
+                    alias: entry.alias.clone(),
+                    private_key: keypair_to_bech32(&entry.key_pair)?,
+                })
+            })
+            .collect::<SuiChainResult<Vec<StoredKey>>>()?;
+        let plaintext = serde_json::to_vec(&stored_keys)
+            .map(Zeroizing::new)
+            .map_err(|e| keystore_error(path, e))?;
+
+        let mut salt = [0u8; SALT_LENGTH];
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let kdf_params = KdfParams::default();
+        let cipher = derive_cipher(password, &salt, kdf_params)?;
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|e| keystore_error(path, e))?;
+
+        let file = EncryptedKeystoreFile {
+            version: KEYSTORE_VERSION,
+            kdf: "argon2id".to_string(),
+            cipher: "aes-256-gcm".to_string(),
+            kdf_params,
+            salt: BASE64_STANDARD.encode(salt),
+            nonce: BASE64_STANDARD.encode(nonce),
+            ciphertext: BASE64_STANDARD.encode(ciphertext),
+        };
+        let content = serde_json::to_string_pretty(&file).map_err(|e| keystore_error(path, e))?;
+        fs::write(path, content).map_err(|e| keystore_error(path, e))
+    }
+
+    /// Adds a key under `alias`, which must not be used yet.
+    pub fn add(
+        &mut self,
+        alias: impl Into<String>,
+        key_pair: SuiKeyPair,
+    ) -> SuiChainResult<SuiAddress> {
+        let alias = alias.into();
+        if self.keys.iter().any(|entry| entry.alias == alias) {
+            return Err(SuiChainError::Keystore(format!(
+                "alias {} already exists",
+                alias
+            )));
+        }
+        let address = SuiAddress::from(&key_pair.public());
+        self.keys.push(KeystoreEntry {
+            alias,
+            address,
+            key_pair,
+        });
+        Ok(address)
+    }
+
+    pub fn remove(&mut self, alias_or_address: &str) -> SuiChainResult<()> {
+        let index = self.position(alias_or_address)?;
+        self.keys.remove(index);
+        Ok(())
+    }
+
+    /// Aliases and addresses of the stored keys, in insertion order.
+    pub fn aliases(&self) -> Vec<(String, SuiAddress)> {
+        self.keys
+            .iter()
+            .map(|entry| (entry.alias.clone(), entry.address))
+            .collect()
+    }
+
+    /// Looks a key up by alias, or by address when no alias matches.
+    pub fn get(&self, alias_or_address: &str) -> SuiChainResult<&SuiKeyPair> {
+        let index = self.position(alias_or_address)?;
+        Ok(&self.keys[index].key_pair)
+    }
+
+    fn position(&self, alias_or_address: &str) -> SuiChainResult<usize> {
+        if let Some(index) = self
+            .keys
+            .iter()
+            .position(|entry| entry.alias == alias_or_address)
+        {
+            return Ok(index);
+        }
+        alias_or_address
+            .parse::<SuiAddress>()
+            .ok()
+            .and_then(|address| self.keys.iter().position(|entry| entry.address == address))
+            .ok_or_else(|| SuiChainError::NotFound(format!("key {}", alias_or_address)))
+    }
+}
+
+fn derive_cipher(password: &str, salt: &[u8], kdf_params: KdfParams) -> SuiChainResult<Aes256Gcm> {
+    let params = Params::new(
+        kdf_params.m_cost,
+        kdf_params.t_cost,
+        kdf_params.p_cost,
+        Some(32),
+    )
+    .map_err(|e| SuiChainError::Keystore(e.to_string()))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut *key)
+        .map_err(|e| SuiChainError::Keystore(e.to_string()))?;
+    Aes256Gcm::new_from_slice(&*key).map_err(|e| SuiChainError::Keystore(e.to_string()))
+}
+
+fn decode_field(path: &Path, value: &str) -> SuiChainResult<Vec<u8>> {
+    BASE64_STANDARD
+        .decode(value)
+        .map_err(|e| keystore_error(path, e))
+}
+
+fn keystore_error(path: &Path, error: impl std::fmt::Display) -> SuiChainError {
+    SuiChainError::Keystore(format!("{}: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use sui_types::base_types::SuiAddress;
+    use sui_types::crypto::{EncodeDecodeBase64, SignatureScheme};
+
+    use super::SuiKeystore;
+    use crate::sui_error::SuiChainError;
+    use crate::sui_func::generate_keypair;
+
+    fn sample_keystore() -> SuiKeystore {
+        let mut keystore = SuiKeystore::new();
+        for (alias, scheme) in [
+            ("main", SignatureScheme::ED25519),
+            ("hot", SignatureScheme::Secp256k1),
+            ("cold", SignatureScheme::Secp256r1),
+        ] {
+            keystore
+                .add(alias, generate_keypair(scheme).unwrap())
+                .unwrap();
+        }
+        keystore
+    }
+
+    #[test]
+    fn encrypted_keystore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.keystore");
+        let keystore = sample_keystore();
+        keystore.save_encrypted(&path, "correct horse").unwrap();
+
+        let opened = SuiKeystore::open_encrypted(&path, "correct horse").unwrap();
+        assert_eq!(opened.aliases(), keystore.aliases());
+        for (alias, _) in keystore.aliases() {
+            assert_eq!(
+                opened.get(&alias).unwrap().public(),
+                keystore.get(&alias).unwrap().public()
+            );
+        }
+    }
+
+    #[test]
+    fn encrypted_keystore_uses_the_stored_kdf_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.keystore");
+        sample_keystore()
+            .save_encrypted(&path, "correct horse")
+            .unwrap();
+        let mut file: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            file["kdf_params"]["m_cost"],
+            serde_json::json!(argon2::Params::DEFAULT_M_COST)
+        );
+
+        // Files without parameters were written with the Argon2 defaults.
+        let mut params = file.as_object_mut().unwrap().remove("kdf_params").unwrap();
+        fs::write(&path, file.to_string()).unwrap();
+        assert!(SuiKeystore::open_encrypted(&path, "correct horse").is_ok());
+
+        params["t_cost"] = serde_json::json!(argon2::Params::DEFAULT_T_COST + 1);
+        file["kdf_params"] = params;
+        fs::write(&path, file.to_string()).unwrap();
+        assert!(matches!(
+            SuiKeystore::open_encrypted(&path, "correct horse"),
+            Err(SuiChainError::Keystore(_))
+        ));
+    }
+
+    #[test]
+    fn wrong_password_is_a_keystore_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.keystore");
+        sample_keystore()
+            .save_encrypted(&path, "correct horse")
+            .unwrap();
+
+        let result = SuiKeystore::open_encrypted(&path, "battery staple");
+        assert!(matches!(result, Err(SuiChainError::Keystore(_))));
+    }
+
+    #[test]
+    fn loads_sui_cli_keystore_with_aliases() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let aliased = generate_keypair(SignatureScheme::ED25519).unwrap();
+        let unaliased = generate_keypair(SignatureScheme::Secp256k1).unwrap();
+        let keys = vec![aliased.encode_base64(), unaliased.encode_base64()];
+        fs::write(
+            dir.join("sui.keystore"),
+            serde_json::to_string(&keys).unwrap(),
+        )
+        .unwrap();
+        let aliases = serde_json::json!([{
+            "alias": "deployer",
+            "public_key_base64": aliased.public().encode_base64(),
+        }]);
+        fs::write(dir.join("sui.aliases"), aliases.to_string()).unwrap();
+
+        let keystore = SuiKeystore::load_sui_keystore(dir.join("sui.keystore")).unwrap();
+        let aliased_address = SuiAddress::from(&aliased.public());
+        let unaliased_address = SuiAddress::from(&unaliased.public());
+        assert_eq!(
+            keystore.aliases(),
+            vec![
+                ("deployer".to_string(), aliased_address),
+                (unaliased_address.to_string(), unaliased_address),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_by_alias_and_by_address() {
+        let keystore = sample_keystore();
+        let (alias, address) = keystore.aliases()[1].clone();
+        let by_alias = keystore.get(&alias).unwrap().public();
+        let by_address = keystore.get(&address.to_string()).unwrap().public();
+        assert_eq!(by_alias, by_address);
+        assert_eq!(SuiAddress::from(&by_alias), address);
+        assert!(matches!(
+            keystore.get("missing"),
+            Err(SuiChainError::NotFound(_))
+        ));
+    }
+}
@@ -1,11 +1,25 @@
 use std::usize;
 
-use sui_json_rpc_types::{BalanceChange, SuiTransactionBlockData, SuiTransactionBlockResponse};
+use sui_json_rpc_types::{
+    BalanceChange, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponseQuery, SuiTransactionBlockData, SuiTransactionBlockResponse,
+};
+use sui_sdk::SuiClient;
 use sui_types::{
-    base_types::SuiAddress,
-    crypto::{get_key_pair_from_rng, SuiKeyPair},
+    base_types::{ObjectRef, SuiAddress},
+    crypto::{SignatureScheme, SuiKeyPair},
+    gas_coin::{GasCoin, GAS},
+    parse_sui_type_tag,
+    object::Owner,
 };
 
+use crate::sui_error::{SuiChainError, SuiChainResult};
+use crate::sui_func::generate_keypair;
+
+/// Protocol limit on the number of coins a transaction can use for gas.
+/// Most coins a transaction can use for gas payment.
+pub(crate) const MAX_GAS_COINS: usize = 256;
+
 pub trait SuiTransactionFuncExtension {
     fn who_paid_gas(&self) -> String;
     fn get_module_list(&self) -> Vec<String>;
@@ -29,7 +43,7 @@ impl SuiTransactionFuncExtension for SuiTransactionBlockResponse {
             Some(inner_tx_obj) => {
                 for inner_item in inner_tx_obj.data.move_calls().iter() {
                     if inner_item.module.len() == 0 {
-                        dbg!(inner_item);
+                        tracing::debug!(?inner_item, "move call without module name");
                     } else {
                         let module_name_str = inner_item.module.clone();
                         // println!("module: {} / {}",inner_item.module,inner_item.function);
@@ -55,7 +69,7 @@ impl SuiTransactionFuncExtension for SuiTransactionBlockResponse {
             Some(inner_tx_obj) => {
                 for inner_item in inner_tx_obj.data.move_calls().iter() {
                     if inner_item.module.len() == 0 {
-                        dbg!(inner_item);
+                        tracing::debug!(?inner_item, "move call without module name");
                     } else {
                         let module_name_str = inner_item.function.clone();
                         let mut item_found = false;
@@ -89,7 +103,7 @@ impl SuiWalletFuncExtension for Vec<BalanceChange> {
     fn which_currency_go_out_my_account(&self, my_wallet_addr: String) -> Vec<String> {
         let mut result_list = Vec::new();
         for item in self.iter() {
-            let control_addr = format!("{}", item.owner.get_owner_address().unwrap().to_string());
+            let control_addr = sui_owner_address(&item.owner).unwrap_or_default();
             if control_addr.eq(&my_wallet_addr.clone()) {
                 if item.amount < 0 {
                     result_list.push(sui_get_currency_name(
@@ -103,7 +117,7 @@ impl SuiWalletFuncExtension for Vec<BalanceChange> {
     fn which_currency_get_in_my_account(&self, my_wallet_addr: String) -> Vec<String> {
         let mut result_list = Vec::new();
         for item in self.iter() {
-            let control_addr = format!("{}", item.owner.get_owner_address().unwrap().to_string());
+            let control_addr = sui_owner_address(&item.owner).unwrap_or_default();
             if control_addr.eq(&my_wallet_addr.clone()) {
                 if item.amount > 0 {
                     result_list.push(sui_get_currency_name(
@@ -134,7 +148,9 @@ impl SuiWalletFuncExtension for Vec<BalanceChange> {
     fn get_address_list(&self) -> Vec<String> {
         let mut address_list: Vec<String> = Vec::new();
         for item in self.iter() {
-            let c_addr = format!("{}", item.owner.get_owner_address().unwrap().to_string());
+            let Ok(c_addr) = sui_owner_address(&item.owner) else {
+                continue;
+            };
             let mut addr_found = false;
             for a_item in address_list.iter() {
                 if a_item.clone().eq(&c_addr.clone()) {
@@ -149,7 +165,7 @@ impl SuiWalletFuncExtension for Vec<BalanceChange> {
     }
     fn my_balanced_changed(&self, my_wallet_addr: String) -> bool {
         for item in self.iter() {
-            let control_addr = format!("{}", item.owner.get_owner_address().unwrap().to_string());
+            let control_addr = sui_owner_address(&item.owner).unwrap_or_default();
             if control_addr.eq(&my_wallet_addr.clone()) {
                 return true;
             }
@@ -161,8 +177,7 @@ impl SuiWalletFuncExtension for Vec<BalanceChange> {
         loop {
             let mut found_index = usize::MAX;
             for (index, item) in balance_list.iter().enumerate() {
-                let control_addr =
-                    format!("{}", item.owner.get_owner_address().unwrap().to_string());
+                let control_addr = sui_owner_address(&item.owner).unwrap_or_default();
                 if control_addr.eq(&my_wallet_addr.clone()) == false {
                     found_index = index;
                 }
@@ -177,6 +192,13 @@ impl SuiWalletFuncExtension for Vec<BalanceChange> {
     }
 }
 
+pub fn sui_owner_address(owner: &Owner) -> SuiChainResult<String> {
+    owner
+        .get_owner_address()
+        .map(|addr| addr.to_string())
+        .map_err(|_| SuiChainError::InvalidAddress(owner.to_string()))
+}
+
 pub fn sui_get_currency_name(raw_currency: String) -> String {
     if raw_currency
         .eq("0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI")
@@ -213,6 +235,9 @@ pub fn sui_get_currency_name(raw_currency: String) -> String {
         return "SuiReward::SUIREWARD".to_string();
     } else {
         let collection = raw_currency.split("::").collect::<Vec<&str>>();
+        if collection.len() < 3 {
+            return format!("[{}]", raw_currency);
+        }
         format!("[{}::{}]", collection[1], collection[2])
         // "[UNKNOWN]".to_string()
     }
@@ -235,36 +260,53 @@ pub fn sui_format_volume(volume: u128, currency: String) -> String {
 }
 
 #[allow(dead_code)]
-pub fn key_pair_func() {
-    let random_key_pair = SuiKeyPair::Ed25519(get_key_pair_from_rng(&mut rand::rngs::OsRng).1);
-    println!(
-        "random address : {}",
-        SuiAddress::from(&random_key_pair.public())
-    );
-    let to_bytes = random_key_pair.to_bytes();
+pub fn key_pair_func() -> SuiChainResult<()> {
+    for scheme in [
+        SignatureScheme::ED25519,
+        SignatureScheme::Secp256k1,
+        SignatureScheme::Secp256r1,
+    ] {
+        let random_key_pair = generate_keypair(scheme)?;
+        tracing::debug!(
+            %scheme,
+            address = %SuiAddress::from(&random_key_pair.public()),
+            "random key pair"
+        );
+        let to_bytes = random_key_pair.to_bytes();
 
-    let regenerated = SuiKeyPair::from_bytes(&to_bytes).unwrap();
-    // let regenerated = SuiKeyPair::Ed25519(get_key_pair_from_bytes(&to_bytes).unwrap().1);
-    println!(
-        "regenerate address : {}",
-        SuiAddress::from(&regenerated.public())
-    );
+        let regenerated = SuiKeyPair::from_bytes(&to_bytes)
+            .map_err(|e| SuiChainError::InvalidPrivateKey(e.to_string()))?;
+        tracing::debug!(
+            scheme = %regenerated.public().scheme(),
+            address = %SuiAddress::from(&regenerated.public()),
+            "regenerated key pair"
+        );
+    }
+    Ok(())
 }
 
-/*
+/// Whether `coin_type` names SUI, in short or long address form.
+pub fn is_sui_coin_type(coin_type: &str) -> bool {
+    match parse_sui_type_tag(coin_type) {
+        Ok(type_tag) => type_tag == GAS::type_tag(),
+        Err(_) => false,
+    }
+}
 
-pub async fn fetch_sorted_gas_coins(
+/// All SUI coins of `sender` with a non-zero balance, largest first. Returns the
+/// SDK error so it can run inside `SuiNetwork::read`; callers outside the crate
+/// use `SuiNetwork::get_sorted_gas_coins`.
+pub(crate) async fn fetch_sorted_gas_coins(
     rpc_client: &SuiClient,
     sender: &SuiAddress,
-) -> anyhow::Result<Vec<(SuiObjectData, u64)>> {
-
+) -> Result<Vec<(SuiObjectData, u64)>, sui_sdk::error::Error> {
     let mut gas_objects: Vec<(SuiObjectData, u64)> = vec![];
     let mut cursor = None;
     loop {
         let response = rpc_client
             .read_api()
             .get_owned_objects(
-                sender.clone(),
+                *sender,
                 Some(SuiObjectResponseQuery {
                     filter: Some(SuiObjectDataFilter::MatchAll(vec![
                         SuiObjectDataFilter::StructType(GasCoin::type_()),
@@ -280,14 +322,10 @@ pub async fn fetch_sorted_gas_coins(
             .data
             .into_iter()
             .filter_map(|maybe_object| {
-                if let Some(object) = maybe_object.data {
-                    let gas_coin = GasCoin::try_from(&object).unwrap();
-                    let gas_balance = gas_coin.value();
-                    if gas_balance > 0 {
-                        Some((object, gas_balance))
-                    } else {
-                        None
-                    }
+                let object = maybe_object.data?;
+                let gas_balance = GasCoin::try_from(&object).ok()?.value();
+                if gas_balance > 0 {
+                    Some((object, gas_balance))
                 } else {
                     None
                 }
@@ -307,5 +345,20 @@ pub async fn fetch_sorted_gas_coins(
     Ok(gas_objects)
 }
 
-
-*/
+/// Picks coins from a list sorted by `get_sorted_gas_coins` until their balance
+/// covers `required`. Returns `None` when the coins cannot cover it.
+pub fn select_gas_coins(
+    gas_coins: &[(SuiObjectData, u64)],
+    required: u64,
+) -> Option<Vec<ObjectRef>> {
+    let mut selected = Vec::new();
+    let mut total: u64 = 0;
+    for (object, balance) in gas_coins.iter().take(MAX_GAS_COINS) {
+        selected.push(object.object_ref());
+        total = total.saturating_add(*balance);
+        if total >= required {
+            return Some(selected);
+        }
+    }
+    None
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{PublicKey, Signature, SuiSignature};
+use sui_types::multisig::MultiSig;
+use sui_types::signature::GenericSignature;
+use sui_types::transaction::TransactionData;
+
+pub use sui_types::multisig::MultiSigPublicKey;
+
+use crate::sui_error::{SuiChainError, SuiChainResult};
+use crate::sui_signer::{sign_transaction, TransactionSigner};
+
+/// Builds a multisig public key from `(member key, weight)` pairs. A transaction
+/// is valid once the weights of its signers add up to `threshold`.
+pub fn multisig_public_key(
+    members: Vec<(PublicKey, u8)>,
+    threshold: u16,
+) -> SuiChainResult<MultiSigPublicKey> {
+    let (public_keys, weights): (Vec<PublicKey>, Vec<u8>) = members.into_iter().unzip();
+    MultiSigPublicKey::new(public_keys, weights, threshold)
+        .map_err(|e| SuiChainError::Signature(e.to_string()))
+}
+
+pub fn multisig_address(multisig_pk: &MultiSigPublicKey) -> SuiAddress {
+    SuiAddress::from(multisig_pk)
+}
+
+/// Collects one partial signature per member signer. The transaction sender
+/// must be the multisig address.
+pub async fn sign_partial(
+    signers: &[Arc<dyn TransactionSigner>],
+    tx_data: &TransactionData,
+) -> SuiChainResult<Vec<Signature>> {
+    let mut signatures = Vec::with_capacity(signers.len());
+    for signer in signers {
+        signatures.push(sign_transaction(signer.as_ref(), tx_data).await?);
+    }
+    Ok(signatures)
+}
+
+/// Combines partial signatures into the multisig that authorizes the
+/// transaction, ready for `SuiNetwork::execute_signed`. Signatures may come in
+/// any order and repeated signatures of a member are dropped. Fails when a
+/// signer is not a member or the weights stay below the threshold.
+pub fn combine_signatures(
+    multisig_pk: &MultiSigPublicKey,
+    signatures: Vec<Signature>,
+) -> SuiChainResult<GenericSignature> {
+    let mut members = Vec::with_capacity(signatures.len());
+    for signature in signatures {
+        let position = multisig_pk
+            .pubkeys()
+            .iter()
+            .position(|(public_key, _)| public_key.as_ref() == signature.public_key_bytes())
+            .ok_or_else(|| {
+                SuiChainError::Signature(format!(
+                    "{} is not a member of the multisig",
+                    BASE64_STANDARD.encode(signature.public_key_bytes())
+                ))
+            })?;
+        members.push((position, signature));
+    }
+    // `MultiSig::combine` expects the signatures in the order of the member keys,
+    // and the same member signing twice must not count twice.
+    members.sort_by_key(|(position, _)| *position);
+    members.dedup_by_key(|(position, _)| *position);
+
+    let weight: u16 = members
+        .iter()
+        .map(|(position, _)| multisig_pk.pubkeys()[*position].1 as u16)
+        .sum();
+    if weight < *multisig_pk.threshold() {
+        return Err(SuiChainError::Signature(format!(
+            "signature weight {} is below the threshold {}",
+            weight,
+            multisig_pk.threshold()
+        )));
+    }
+    let signatures = members
+        .into_iter()
+        .map(|(_, signature)| GenericSignature::Signature(signature))
+        .collect();
+    let multisig = MultiSig::combine(signatures, multisig_pk.clone())
+        .map_err(|e| SuiChainError::Signature(e.to_string()))?;
+    Ok(GenericSignature::MultiSig(multisig))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::crypto::{Signature, SignatureScheme, Signer, SuiKeyPair};
+    use sui_types::signature::GenericSignature;
+
+    use super::{combine_signatures, multisig_public_key, MultiSigPublicKey};
+    use crate::sui_error::SuiChainError;
+    use crate::sui_func::generate_keypair;
+
+    const MESSAGE: &[u8] = b"multisig test message";
+
+    /// Three members of weight 1 each, any two of them sign.
+    fn two_of_three() -> (Vec<SuiKeyPair>, MultiSigPublicKey) {
+        let members: Vec<SuiKeyPair> = [
+            SignatureScheme::ED25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::Secp256r1,
+        ]
+        .into_iter()
+        .map(|scheme| generate_keypair(scheme).unwrap())
+        .collect();
+        let multisig_pk = multisig_public_key(
+            members.iter().map(|member| (member.public(), 1)).collect(),
+            2,
+        )
+        .unwrap();
+        (members, multisig_pk)
+    }
+
+    fn sign(key_pair: &SuiKeyPair) -> Signature {
+        key_pair.sign(MESSAGE)
+    }
+
+    #[test]
+    fn combines_signatures_given_out_of_order() {
+        let (members, multisig_pk) = two_of_three();
+        let combined =
+            combine_signatures(&multisig_pk, vec![sign(&members[2]), sign(&members[0])]).unwrap();
+        let GenericSignature::MultiSig(multisig) = combined else {
+            panic!("expected a multisig");
+        };
+        let expected = [&members[0], &members[2]]
+            .into_iter()
+            .map(|member| {
+                GenericSignature::Signature(sign(member))
+                    .to_compressed()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(multisig.get_sigs(), expected.as_slice());
+        assert_eq!(multisig.get_indices().unwrap(), vec![0, 2]);
+    }
+
+    #[test]
+    fn duplicate_signer_counts_once() {
+        let (members, multisig_pk) = two_of_three();
+        let result = combine_signatures(&multisig_pk, vec![sign(&members[1]), sign(&members[1])]);
+        assert!(
+            matches!(result, Err(SuiChainError::Signature(message)) if message.contains("below"))
+        );
+    }
+
+    #[test]
+    fn below_threshold_is_an_error() {
+        let (members, multisig_pk) = two_of_three();
+        let result = combine_signatures(&multisig_pk, vec![sign(&members[0])]);
+        assert!(
+            matches!(result, Err(SuiChainError::Signature(message)) if message.contains("below"))
+        );
+    }
+
+    #[test]
+    fn non_member_is_rejected() {
+        let (members, multisig_pk) = two_of_three();
+        let outsider = generate_keypair(SignatureScheme::ED25519).unwrap();
+        let result = combine_signatures(
+            &multisig_pk,
+            vec![sign(&members[0]), sign(&members[1]), sign(&outsider)],
+        );
+        assert!(
+            matches!(result, Err(SuiChainError::Signature(message)) if message.contains("member"))
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use fastcrypto::traits::ToFromBytes;
+use sui_types::crypto::SuiKeyPair;
+use sui_types::signature::GenericSignature;
+use sui_types::transaction::TransactionData;
+
+use crate::sui_error::{SuiChainError, SuiChainResult};
+use crate::sui_signer::{sign_transaction, KeyPairSigner};
+
+/// Serializes an unsigned transaction as base64 BCS, the format used by
+/// `sui client` and `sui keytool` for transaction bytes.
+pub fn transaction_to_base64(tx_data: &TransactionData) -> SuiChainResult<String> {
+    let tx_bytes =
+        bcs::to_bytes(tx_data).map_err(|e| SuiChainError::InvalidTransaction(e.to_string()))?;
+    Ok(BASE64_STANDARD.encode(tx_bytes))
+}
+
+pub fn transaction_from_base64(tx_bytes: &str) -> SuiChainResult<TransactionData> {
+    let tx_bytes = BASE64_STANDARD
+        .decode(tx_bytes.trim())
+        .map_err(|e| SuiChainError::InvalidTransaction(e.to_string()))?;
+    bcs::from_bytes(&tx_bytes).map_err(|e| SuiChainError::InvalidTransaction(e.to_string()))
+}
+
+/// Signs exported transaction bytes without any network access and returns the
+/// base64 signature, flag, signature and public key concatenated.
+pub fn sign_transaction_offline(tx_bytes: &str, key_pair: &SuiKeyPair) -> SuiChainResult<String> {
+    let tx_data = transaction_from_base64(tx_bytes)?;
+    // A key pair signer never waits, so blocking on it does not stall anything.
+    let signer = KeyPairSigner::new(key_pair.copy());
+    let signature = futures::executor::block_on(sign_transaction(&signer, &tx_data))?;
+    Ok(BASE64_STANDARD.encode(signature.as_ref()))
+}
+
+/// Parses a base64 signature as produced by `sign_transaction_offline`, or a
+/// serialized multisig.
+pub fn signature_from_base64(signature: &str) -> SuiChainResult<GenericSignature> {
+    let signature = BASE64_STANDARD
+        .decode(signature.trim())
+        .map_err(|e| SuiChainError::Signature(e.to_string()))?;
+    GenericSignature::from_bytes(&signature).map_err(|e| SuiChainError::Signature(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use shared_crypto::intent::{Intent, IntentMessage};
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::crypto::SignatureScheme;
+    use sui_types::signature::{GenericSignature, VerifyParams};
+    use sui_types::transaction::TransactionData;
+
+    use super::{
+        sign_transaction_offline, signature_from_base64, transaction_from_base64,
+        transaction_to_base64,
+    };
+    use crate::sui_error::SuiChainError;
+    use crate::sui_func::generate_keypair;
+
+    fn transfer(sender: SuiAddress) -> TransactionData {
+        TransactionData::new_transfer_sui(
+            SuiAddress::random_for_testing_only(),
+            sender,
+            Some(1_000),
+            random_object_ref(),
+            10_000_000,
+            1_000,
+        )
+    }
+
+    #[test]
+    fn transaction_bytes_round_trip() {
+        let tx_data = transfer(SuiAddress::random_for_testing_only());
+        let tx_bytes = transaction_to_base64(&tx_data).unwrap();
+        assert_eq!(transaction_from_base64(&tx_bytes).unwrap(), tx_data);
+        assert!(matches!(
+            transaction_from_base64("not base64!"),
+            Err(SuiChainError::InvalidTransaction(_))
+        ));
+    }
+
+    #[test]
+    fn offline_signature_decodes_and_verifies() {
+        for scheme in [
+            SignatureScheme::ED25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::Secp256r1,
+        ] {
+            let key_pair = generate_keypair(scheme.clone()).unwrap();
+            let sender = SuiAddress::from(&key_pair.public());
+            let tx_data = transfer(sender);
+            let tx_bytes = transaction_to_base64(&tx_data).unwrap();
+
+            let signature = sign_transaction_offline(&tx_bytes, &key_pair).unwrap();
+            let GenericSignature::Signature(signature) = signature_from_base64(&signature).unwrap()
+            else {
+                panic!("expected a single key signature");
+            };
+            let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data);
+            signature
+                .verify_secure(&intent_msg, sender, scheme)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn malformed_signature_is_rejected() {
+        assert!(matches!(
+            signature_from_base64("AAAA"),
+            Err(SuiChainError::Signature(_))
+        ));
+    }
+}
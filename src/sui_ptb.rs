@@ -0,0 +1,259 @@
+use serde::Serialize;
+use sui_json_rpc_types::{DryRunTransactionBlockResponse, SuiObjectDataOptions};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, Command, ObjectArg, TransactionData, TransactionDataAPI};
+use sui_types::{Identifier, TypeTag};
+
+use crate::model::execution::ExecutionOutcome;
+use crate::model::preview::DryRunPreview;
+use crate::sui::SuiNetwork;
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+/// Composes several commands into one atomic programmable transaction block.
+///
+/// Every command returns the `Argument` of its result, so it can be passed on to
+/// the next command. Gas coins and budget are chosen when the block is executed.
+pub struct TransactionBlockBuilder {
+    network: SuiNetwork,
+    ptb: ProgrammableTransactionBuilder,
+    input_objects: Vec<ObjectID>,
+    gas_spend: u64,
+    sender: Option<SuiAddress>,
+    gas_sponsor: Option<SuiAddress>,
+}
+
+impl TransactionBlockBuilder {
+    pub(crate) fn new(network: SuiNetwork) -> Self {
+        TransactionBlockBuilder {
+            network,
+            ptb: ProgrammableTransactionBuilder::new(),
+            input_objects: Vec::new(),
+            gas_spend: 0,
+            sender: None,
+            gas_sponsor: None,
+        }
+    }
+
+    /// The coin paying for gas, usable as a source of SUI in `split_coins`.
+    pub fn gas(&self) -> Argument {
+        Argument::GasCoin
+    }
+
+    pub fn pure<T: Serialize>(&mut self, value: T) -> SuiChainResult<Argument> {
+        self.ptb
+            .pure(value)
+            .map_err(|e| SuiChainError::TransactionBuild(e.to_string()))
+    }
+
+    /// Adds an object input, looking up whether it is owned, immutable or shared.
+    /// Shared objects are used mutably; objects owned by another object are
+    /// rejected.
+    pub async fn object(&mut self, object_id: ObjectID) -> SuiChainResult<Argument> {
+        self.resolve_object(object_id, true).await
+    }
+
+    /// Adds a shared object by immutable reference, as required for the clock.
+    pub async fn read_only_object(&mut self, object_id: ObjectID) -> SuiChainResult<Argument> {
+        self.resolve_object(object_id, false).await
+    }
+
+    /// Adds an owned object whose reference is already known, skipping the lookup.
+    pub fn owned_object(&mut self, object_ref: ObjectRef) -> SuiChainResult<Argument> {
+        self.input_objects.push(object_ref.0);
+        self.ptb
+            .obj(ObjectArg::ImmOrOwnedObject(object_ref))
+            .map_err(|e| SuiChainError::TransactionBuild(e.to_string()))
+    }
+
+    async fn resolve_object(
+        &mut self,
+        object_id: ObjectID,
+        mutable: bool,
+    ) -> SuiChainResult<Argument> {
+        let object = self
+            .network
+            .get_object(object_id, SuiObjectDataOptions::new().with_owner())
+            .await?;
+        let object_arg = match object.owner {
+            Some(Owner::Shared {
+                initial_shared_version,
+            }) => ObjectArg::SharedObject {
+                id: object_id,
+                initial_shared_version,
+                mutable,
+            },
+            Some(Owner::AddressOwner(_)) | Some(Owner::Immutable) => {
+                ObjectArg::ImmOrOwnedObject(object.object_ref())
+            }
+            // Objects owned by another object can only be reached through their
+            // parent, they are not transaction inputs.
+            Some(owner) => {
+                return Err(SuiChainError::TransactionBuild(format!(
+                    "{} cannot be a transaction input, it is owned by {}",
+                    object_id, owner
+                )))
+            }
+            None => return Err(SuiChainError::NotFound(format!("owner of {}", object_id))),
+        };
+        self.input_objects.push(object_id);
+        self.ptb
+            .obj(object_arg)
+            .map_err(|e| SuiChainError::TransactionBuild(e.to_string()))
+    }
+
+    /// Splits `amounts` off `coin` and returns one argument per new coin.
+    pub fn split_coins(
+        &mut self,
+        coin: Argument,
+        amounts: Vec<u64>,
+    ) -> SuiChainResult<Vec<Argument>> {
+        if coin == Argument::GasCoin {
+            self.gas_spend = amounts.iter().fold(self.gas_spend, |total, amount| {
+                total.saturating_add(*amount)
+            });
+        }
+        let count = amounts.len();
+        let amounts = amounts
+            .into_iter()
+            .map(|amount| self.pure(amount))
+            .collect::<SuiChainResult<Vec<Argument>>>()?;
+        let result = self.ptb.command(Command::SplitCoins(coin, amounts));
+        Ok(nested_results(result, count))
+    }
+
+    pub fn merge_coins(&mut self, target: Argument, sources: Vec<Argument>) {
+        self.ptb.command(Command::MergeCoins(target, sources));
+    }
+
+    pub fn transfer_objects(
+        &mut self,
+        objects: Vec<Argument>,
+        recipient: SuiAddress,
+    ) -> SuiChainResult<()> {
+        let recipient = self.pure(recipient)?;
+        self.ptb
+            .command(Command::TransferObjects(objects, recipient));
+        Ok(())
+    }
+
+    /// Calls `package::module::function`. The returned argument refers to the
+    /// first return value; use `Argument::NestedResult` to reach the others.
+    pub fn move_call(
+        &mut self,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<Argument>,
+    ) -> SuiChainResult<Argument> {
+        let module =
+            Identifier::new(module).map_err(|e| SuiChainError::TransactionBuild(e.to_string()))?;
+        let function = Identifier::new(function)
+            .map_err(|e| SuiChainError::TransactionBuild(e.to_string()))?;
+        Ok(self.ptb.command(Command::move_call(
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        )))
+    }
+
+    /// Publishes compiled modules and returns the `UpgradeCap`, which has to be
+    /// transferred, usually to the sender.
+    pub fn publish(&mut self, modules: Vec<Vec<u8>>, dependencies: Vec<ObjectID>) -> Argument {
+        self.ptb.publish_upgradeable(modules, dependencies)
+    }
+
+    /// Builds the block for another sender than the configured signer, e.g. a
+    /// multisig address. Gas is paid from the coins of that sender.
+    pub fn set_sender(&mut self, sender: SuiAddress) {
+        self.sender = Some(sender);
+    }
+
+    /// Lets `sponsor` own and pay the gas. Execute the result of `build` with
+    /// `SuiNetwork::execute_sponsored`. SUI can not be split from `gas()` then.
+    pub fn set_gas_sponsor(&mut self, sponsor: SuiAddress) {
+        self.gas_sponsor = Some(sponsor);
+    }
+
+    /// Signs and executes the block.
+    pub async fn execute(self) -> SuiChainResult<ExecutionOutcome> {
+        if self.gas_sponsor.is_some() {
+            return Err(SuiChainError::TransactionBuild(
+                "sponsored blocks are executed with SuiNetwork::execute_sponsored".to_string(),
+            ));
+        }
+        let network = self.network.clone();
+        let tx_data = self.build().await?;
+        let sender = tx_data.sender();
+        if sender != network.signer_address()? {
+            return Err(SuiChainError::Signature(format!(
+                "the configured signer can not sign for {}; sign the result of build() with \
+                 sign_partial and combine_signatures, then use SuiNetwork::execute_signed",
+                sender
+            )));
+        }
+        let outcome = network.execute_transaction_data(tx_data).await?;
+        tracing::info!(digest = %outcome.digest, %sender, "transaction block executed");
+        Ok(outcome)
+    }
+
+    /// Selects gas and returns the unsigned transaction, for signing elsewhere.
+    pub async fn build(self) -> SuiChainResult<TransactionData> {
+        let (tx_data, dry_run) = self.prepare().await?;
+        SuiNetwork::ensure_dry_run_success(&dry_run)?;
+        Ok(tx_data)
+    }
+
+    /// Simulates the block without executing it.
+    pub async fn dry_run(self) -> SuiChainResult<DryRunPreview> {
+        let (tx_data, dry_run) = self.prepare().await?;
+        Ok(SuiNetwork::dry_run_preview(&tx_data, dry_run))
+    }
+
+    async fn prepare(self) -> SuiChainResult<(TransactionData, DryRunTransactionBlockResponse)> {
+        let sender = match self.sender {
+            Some(sender) => sender,
+            None => self.network.signer_address()?,
+        };
+        match self.gas_sponsor {
+            Some(sponsor) => {
+                if self.gas_spend > 0 {
+                    return Err(SuiChainError::TransactionBuild(
+                        "sponsored transactions can not split the gas coin".to_string(),
+                    ));
+                }
+                self.network
+                    .prepare_sponsored_transaction(
+                        sender,
+                        sponsor,
+                        self.ptb.finish(),
+                        &self.input_objects,
+                    )
+                    .await
+            }
+            None => {
+                self.network
+                    .prepare_transaction(
+                        sender,
+                        self.ptb.finish(),
+                        self.gas_spend,
+                        &self.input_objects,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+fn nested_results(result: Argument, count: usize) -> Vec<Argument> {
+    match result {
+        Argument::Result(index) => (0..count)
+            .map(|position| Argument::NestedResult(index, position as u16))
+            .collect(),
+        other => vec![other],
+    }
+}
@@ -0,0 +1,117 @@
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    pub jitter: bool,
+    pub is_retryable: fn(&SuiChainError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: true,
+            is_retryable: SuiChainError::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Delay before the given retry, `attempt` starting at 1 for the first retry.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        // Clamp in seconds first, `Duration::mul_f64` panics once the delay overflows.
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let seconds = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let backoff = Duration::from_secs_f64(seconds.min(self.max_backoff.as_secs_f64()).max(0.0));
+        if self.jitter {
+            // Equal jitter: keep half of the delay and randomize the other half.
+            let half = backoff / 2;
+            half + half.mul_f64(rand::thread_rng().gen::<f64>())
+        } else {
+            backoff
+        }
+    }
+
+    pub async fn run<T, F, Fut>(&self, mut operation: F) -> SuiChainResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = SuiChainResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(error) if attempt < self.max_attempts && (self.is_retryable)(&error) => {
+                    let backoff = self.backoff(attempt);
+                    tracing::debug!(attempt, ?backoff, %error, "retrying read call");
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    fn unlimited_policy(jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: u32::MAX,
+            jitter,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn backoff_grows_until_max_backoff() {
+        let policy = unlimited_policy(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(10), policy.max_backoff);
+    }
+
+    #[test]
+    fn backoff_does_not_overflow_for_large_attempts() {
+        let policy = unlimited_policy(false);
+        for attempt in [67, 1_000, i32::MAX as u32, u32::MAX] {
+            assert_eq!(policy.backoff(attempt), policy.max_backoff);
+        }
+    }
+
+    #[test]
+    fn jitter_keeps_backoff_between_half_and_full_delay() {
+        let policy = unlimited_policy(true);
+        for attempt in [1, 2, 5, 100, u32::MAX] {
+            let full = unlimited_policy(false).backoff(attempt);
+            for _ in 0..100 {
+                let backoff = policy.backoff(attempt);
+                assert!(backoff >= full / 2 && backoff <= full, "{:?}", backoff);
+            }
+        }
+    }
+}
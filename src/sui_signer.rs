@@ -0,0 +1,328 @@
+use async_trait::async_trait;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{PublicKey, Signature, SignatureScheme, Signer, SuiKeyPair, SuiSignature};
+use sui_types::transaction::TransactionData;
+
+use crate::sui_error::{SuiChainError, SuiChainResult};
+
+/// Signs transactions for `SuiNetwork`. Implementations may hold the key in
+/// memory or forward the request to an external signer.
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    fn public_key(&self) -> PublicKey;
+
+    fn address(&self) -> SuiAddress {
+        SuiAddress::from(&self.public_key())
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        self.public_key().scheme()
+    }
+
+    /// Signs the BCS bytes of an intent message. The returned signature carries
+    /// the scheme flag and the public key, as Sui expects.
+    async fn sign(&self, intent_message: &[u8]) -> SuiChainResult<Signature>;
+
+    /// The key pair, for signers that hold it in memory.
+    fn key_pair(&self) -> Option<&SuiKeyPair> {
+        None
+    }
+}
+
+/// Signs with a key pair held in memory.
+pub struct KeyPairSigner {
+    key_pair: SuiKeyPair,
+}
+
+impl KeyPairSigner {
+    pub fn new(key_pair: SuiKeyPair) -> Self {
+        KeyPairSigner { key_pair }
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for KeyPairSigner {
+    fn public_key(&self) -> PublicKey {
+        self.key_pair.public()
+    }
+
+    async fn sign(&self, intent_message: &[u8]) -> SuiChainResult<Signature> {
+        Ok(self.key_pair.sign(&intent_message_digest(intent_message)))
+    }
+
+    fn key_pair(&self) -> Option<&SuiKeyPair> {
+        Some(&self.key_pair)
+    }
+}
+
+/// Signs `tx_data` as a Sui transaction and checks the signature against the
+/// signer address before returning it.
+pub async fn sign_transaction(
+    signer: &dyn TransactionSigner,
+    tx_data: &TransactionData,
+) -> SuiChainResult<Signature> {
+    let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data.clone());
+    let raw_tx = bcs::to_bytes(&intent_msg).map_err(|e| SuiChainError::Signature(e.to_string()))?;
+    let signature = signer.sign(&raw_tx).await?;
+    signature
+        .verify_secure(&intent_msg, signer.address(), signer.scheme())
+        .map_err(|e| SuiChainError::Signature(e.to_string()))?;
+    Ok(signature)
+}
+
+/// Sui signs the Blake2b-256 digest of the intent message, not the message itself.
+pub fn intent_message_digest(intent_message: &[u8]) -> Vec<u8> {
+    let mut hasher = Blake2b256::new();
+    hasher.update(intent_message);
+    hasher.finalize().to_vec()
+}
+
+#[cfg(unix)]
+pub use remote::{serve_signer, RemoteSigner};
+
+/// Signing over a Unix socket with one JSON request and one JSON response per
+/// line. `{"method":"public_key"}` answers `{"public_key":"<base64>"}` and
+/// `{"method":"sign","intent_message":"<base64>"}` answers
+/// `{"signature":"<base64>"}`. Failures are answered with `{"error":"..."}`.
+#[cfg(unix)]
+mod remote {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use fastcrypto::traits::ToFromBytes;
+    use serde::{Deserialize, Serialize};
+    use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{UnixListener, UnixStream};
+
+    use super::TransactionSigner;
+    use crate::sui_error::{SuiChainError, SuiChainResult};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "method", rename_all = "snake_case")]
+    enum SignerRequest {
+        PublicKey,
+        Sign { intent_message: String },
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    struct SignerResponse {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        public_key: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    }
+
+    /// Client of a signing process listening on a Unix socket. The public key is
+    /// fetched once on `connect`, every signature opens a new connection.
+    pub struct RemoteSigner {
+        socket_path: PathBuf,
+        public_key: PublicKey,
+    }
+
+    impl RemoteSigner {
+        pub async fn connect(socket_path: impl AsRef<Path>) -> SuiChainResult<Self> {
+            let socket_path = socket_path.as_ref().to_path_buf();
+            let response = request(&socket_path, &SignerRequest::PublicKey).await?;
+            let public_key = response
+                .public_key
+                .ok_or_else(|| SuiChainError::Signature("signer sent no public key".to_string()))?;
+            let public_key = PublicKey::decode_base64(&public_key)
+                .map_err(|e| SuiChainError::Signature(e.to_string()))?;
+            Ok(RemoteSigner {
+                socket_path,
+                public_key,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl TransactionSigner for RemoteSigner {
+        fn public_key(&self) -> PublicKey {
+            self.public_key.clone()
+        }
+
+        async fn sign(&self, intent_message: &[u8]) -> SuiChainResult<Signature> {
+            let sign_request = SignerRequest::Sign {
+                intent_message: BASE64_STANDARD.encode(intent_message),
+            };
+            let response = request(&self.socket_path, &sign_request).await?;
+            let signature = response
+                .signature
+                .ok_or_else(|| SuiChainError::Signature("signer sent no signature".to_string()))?;
+            let signature = BASE64_STANDARD
+                .decode(signature)
+                .map_err(|e| SuiChainError::Signature(e.to_string()))?;
+            Signature::from_bytes(&signature).map_err(|e| SuiChainError::Signature(e.to_string()))
+        }
+    }
+
+    async fn request(
+        socket_path: &Path,
+        signer_request: &SignerRequest,
+    ) -> SuiChainResult<SignerResponse> {
+        let stream = UnixStream::connect(socket_path)
+            .await
+            .map_err(|e| signer_error(socket_path, e))?;
+        let (reader, mut writer) = stream.into_split();
+        let mut line =
+            serde_json::to_string(signer_request).map_err(|e| signer_error(socket_path, e))?;
+        line.push('\n');
+        writer
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| signer_error(socket_path, e))?;
+
+        let mut response_line = String::new();
+        BufReader::new(reader)
+            .read_line(&mut response_line)
+            .await
+            .map_err(|e| signer_error(socket_path, e))?;
+        let response: SignerResponse =
+            serde_json::from_str(&response_line).map_err(|e| signer_error(socket_path, e))?;
+        match response.error {
+            Some(error) => Err(signer_error(socket_path, error)),
+            None => Ok(response),
+        }
+    }
+
+    /// Answers `RemoteSigner` requests with `signer`, until the listener fails.
+    /// Meant as a local stand-in for an external signing service.
+    pub async fn serve_signer(
+        listener: UnixListener,
+        signer: Arc<dyn TransactionSigner>,
+    ) -> SuiChainResult<()> {
+        loop {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .map_err(|e| SuiChainError::Signature(e.to_string()))?;
+            let signer = signer.clone();
+            tokio::spawn(async move {
+                if let Err(error) = answer(stream, signer).await {
+                    tracing::warn!(%error, "signer request failed");
+                }
+            });
+        }
+    }
+
+    async fn answer(stream: UnixStream, signer: Arc<dyn TransactionSigner>) -> std::io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let response = match serde_json::from_str::<SignerRequest>(&line) {
+                Ok(SignerRequest::PublicKey) => SignerResponse {
+                    public_key: Some(signer.public_key().encode_base64()),
+                    ..SignerResponse::default()
+                },
+                Ok(SignerRequest::Sign { intent_message }) => {
+                    match BASE64_STANDARD.decode(intent_message) {
+                        Ok(intent_message) => match signer.sign(&intent_message).await {
+                            Ok(signature) => SignerResponse {
+                                signature: Some(BASE64_STANDARD.encode(signature.as_ref())),
+                                ..SignerResponse::default()
+                            },
+                            Err(e) => error_response(e),
+                        },
+                        Err(e) => error_response(e),
+                    }
+                }
+                Err(e) => error_response(e),
+            };
+            let mut response_line = serde_json::to_string(&response)?;
+            response_line.push('\n');
+            writer.write_all(response_line.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    fn error_response(error: impl std::fmt::Display) -> SignerResponse {
+        SignerResponse {
+            error: Some(error.to_string()),
+            ..SignerResponse::default()
+        }
+    }
+
+    fn signer_error(socket_path: &Path, error: impl std::fmt::Display) -> SuiChainError {
+        SuiChainError::Signature(format!("{}: {}", socket_path.display(), error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use std::sync::Arc;
+
+    use shared_crypto::intent::{Intent, IntentMessage};
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::crypto::{SignatureScheme, SuiSignature};
+    use sui_types::transaction::TransactionData;
+    #[cfg(unix)]
+    use tokio::net::UnixListener;
+
+    #[cfg(unix)]
+    use super::{serve_signer, RemoteSigner};
+    use super::{sign_transaction, KeyPairSigner, TransactionSigner};
+    use crate::sui_func::generate_keypair;
+
+    fn transfer(sender: SuiAddress) -> TransactionData {
+        TransactionData::new_transfer_sui(
+            SuiAddress::random_for_testing_only(),
+            sender,
+            Some(1_000),
+            random_object_ref(),
+            10_000_000,
+            1_000,
+        )
+    }
+
+    #[tokio::test]
+    async fn key_pair_signer_signs_and_verifies_each_scheme() {
+        for scheme in [
+            SignatureScheme::ED25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::Secp256r1,
+        ] {
+            let signer = KeyPairSigner::new(generate_keypair(scheme.clone()).unwrap());
+            assert_eq!(signer.scheme(), scheme);
+
+            let tx_data = transfer(signer.address());
+            let signature = sign_transaction(&signer, &tx_data).await.unwrap();
+            assert_eq!(signature.as_ref()[0], scheme.flag());
+
+            let other = KeyPairSigner::new(generate_keypair(scheme.clone()).unwrap());
+            let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data);
+            assert!(signature
+                .verify_secure(&intent_msg, other.address(), scheme)
+                .is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn remote_signer_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let key_pair = generate_keypair(SignatureScheme::ED25519).unwrap();
+        let local: Arc<dyn TransactionSigner> = Arc::new(KeyPairSigner::new(key_pair));
+        let server = tokio::spawn(serve_signer(listener, local.clone()));
+
+        let remote = RemoteSigner::connect(&socket_path).await.unwrap();
+        assert_eq!(remote.address(), local.address());
+        assert_eq!(remote.scheme(), SignatureScheme::ED25519);
+
+        let tx_data = transfer(remote.address());
+        let signature = sign_transaction(&remote, &tx_data).await.unwrap();
+        let local_signature = sign_transaction(local.as_ref(), &tx_data).await.unwrap();
+        assert_eq!(signature.as_ref(), local_signature.as_ref());
+
+        server.abort();
+    }
+}
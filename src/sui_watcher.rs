@@ -0,0 +1,132 @@
+use std::collections::{BTreeSet, HashSet};
+use std::str::FromStr;
+
+use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
+use sui_types::base_types::SuiAddress;
+use sui_types::digests::TransactionDigest;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::model::balance::AccountHistory;
+use crate::model::checkpoint::CheckpointSummary;
+use crate::sui::SuiNetwork;
+use crate::sui_checkpoint::CheckpointStreamOptions;
+use crate::sui_error::{SuiChainError, SuiChainResult};
+use crate::sui_follower::{CheckpointFollower, MAX_BACKOFF_ATTEMPT};
+
+pub type WalletEvent = (SuiAddress, AccountHistory);
+
+/// Follows new checkpoints and reports the history of a set of watched
+/// addresses, as `organize_balance_list` sees it.
+pub struct WalletWatcher {
+    follower: CheckpointFollower,
+    addresses: HashSet<SuiAddress>,
+    options: CheckpointStreamOptions,
+}
+
+impl WalletWatcher {
+    pub fn new(
+        follower: CheckpointFollower,
+        addresses: impl IntoIterator<Item = SuiAddress>,
+    ) -> Self {
+        WalletWatcher {
+            follower,
+            addresses: addresses.into_iter().collect(),
+            options: CheckpointStreamOptions {
+                transaction_options: SuiTransactionBlockResponseOptions::new()
+                    .with_input()
+                    .with_effects()
+                    .with_balance_changes(),
+                ..CheckpointStreamOptions::default()
+            },
+        }
+    }
+
+    pub fn watch(&mut self, address: SuiAddress) {
+        self.addresses.insert(address);
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.follower.cursor()
+    }
+
+    /// Runs the watcher on the tokio runtime and returns its events, along with
+    /// the task handle that reports why the watcher stopped.
+    pub fn spawn(
+        self,
+        buffer: usize,
+    ) -> (mpsc::Receiver<WalletEvent>, JoinHandle<SuiChainResult<()>>) {
+        let (sender, receiver) = mpsc::channel(buffer);
+        let handle = tokio::spawn(self.run(sender));
+        (receiver, handle)
+    }
+
+    /// Sends an event for every history entry of a watched address, checkpoint
+    /// by checkpoint. Returns once the receiver is dropped, or with an error when
+    /// the follower fails or a checkpoint lists a digest that cannot be parsed.
+    pub async fn run(mut self, sender: mpsc::Sender<WalletEvent>) -> SuiChainResult<()> {
+        loop {
+            let checkpoint = tokio::select! {
+                checkpoint = self.follower.next() => checkpoint?,
+                _ = sender.closed() => return Ok(()),
+            };
+            for tx_details in self.transactions(&checkpoint).await? {
+                for address in self.touched_addresses(&tx_details) {
+                    for history in SuiNetwork::organize_balance_list(tx_details.clone(), address) {
+                        if sender.send((address, history)).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fetches the transactions of `checkpoint`, retrying RPC errors until it
+    /// succeeds so no checkpoint is skipped.
+    async fn transactions(
+        &self,
+        checkpoint: &CheckpointSummary,
+    ) -> SuiChainResult<Vec<SuiTransactionBlockResponse>> {
+        let digests = checkpoint
+            .transactions
+            .iter()
+            .map(|digest| {
+                TransactionDigest::from_str(digest)
+                    .map_err(|_| SuiChainError::InvalidDigest(digest.clone()))
+            })
+            .collect::<SuiChainResult<Vec<TransactionDigest>>>()?;
+        let network = self.follower.network();
+        let mut failures = 0;
+        loop {
+            match network
+                .multi_get_transactions(&digests, &self.options)
+                .await
+            {
+                Ok(transactions) => return Ok(transactions),
+                Err(error) => {
+                    failures = (failures + 1).min(MAX_BACKOFF_ATTEMPT);
+                    let backoff = network.config().retry_policy.backoff(failures);
+                    tracing::warn!(
+                        checkpoint = checkpoint.sequence_number,
+                        failures,
+                        ?backoff,
+                        %error,
+                        "wallet watcher could not fetch transactions, retrying"
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+    }
+
+    fn touched_addresses(&self, tx_details: &SuiTransactionBlockResponse) -> BTreeSet<SuiAddress> {
+        tx_details
+            .balance_changes
+            .iter()
+            .flatten()
+            .filter_map(|change| change.owner.get_owner_address().ok())
+            .filter(|address| self.addresses.contains(address))
+            .collect()
+    }
+}